@@ -0,0 +1,244 @@
+//! Counting of heap allocations, to find the solvers that allocate the most.
+//!
+//! Counting is opt in. The binary only installs `CountingAllocator` as its global allocator when
+//! it is built with the `count-allocations` feature, since counting slows every allocation down
+//! a little. Without it, `measure` has nothing to count and returns `None`.
+//!
+//! Allocations are counted per thread, so `measure` only sees what the closure allocates on the
+//! calling thread, and other threads allocating at the same time don't disturb the counts.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use color_eyre::eyre::Result;
+
+use crate::solver::{DynSolver, Part};
+
+/// CountingAllocator hands every request on to the system allocator, counting the allocations
+/// and bytes of each thread on the way.
+pub struct CountingAllocator;
+
+/// Set by the first allocation made through `CountingAllocator`, which tells whether it is the
+/// global allocator.
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+struct Counters {
+    count: Cell<u64>,
+    bytes: Cell<u64>,
+    /// Bytes allocated and not yet freed. This can go below zero on a thread that frees memory
+    /// another thread allocated.
+    live: Cell<i64>,
+    peak: Cell<i64>,
+}
+
+thread_local! {
+    // Counters has no destructor and a const initializer, so using it never allocates, which
+    // would recurse into the allocator.
+    static COUNTERS: Counters = const {
+        Counters { count: Cell::new(0), bytes: Cell::new(0), live: Cell::new(0), peak: Cell::new(0) }
+    };
+}
+
+fn record_alloc(size: usize) {
+    COUNTING.store(true, Ordering::Relaxed);
+    // The counters are gone while the thread is being torn down, and those allocations are
+    // simply not counted.
+    let _ = COUNTERS.try_with(|c| {
+        c.count.set(c.count.get() + 1);
+        c.bytes.set(c.bytes.get() + size as u64);
+        c.live.set(c.live.get() + size as i64);
+        c.peak.set(c.peak.get().max(c.live.get()));
+    });
+}
+
+fn record_dealloc(size: usize) {
+    let _ = COUNTERS.try_with(|c| c.live.set(c.live.get() - size as i64));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            // A reallocation is counted as a new allocation that replaces the old one.
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new
+    }
+}
+
+// The tests count allocations, so they always run with the counting allocator.
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// is_counting is true if `CountingAllocator` is the global allocator.
+pub fn is_counting() -> bool {
+    COUNTING.load(Ordering::Relaxed)
+}
+
+/// Allocations is how much something allocated on the heap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Allocations {
+    /// How many allocations were made, reallocations included.
+    pub count: u64,
+    /// The total size of those allocations.
+    pub bytes: u64,
+    /// The most bytes that were allocated at once, on top of what was allocated beforehand.
+    pub peak: u64,
+}
+
+impl Display for Allocations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} allocations, {}, peak {}", self.count, format_bytes(self.bytes), format_bytes(self.peak))
+    }
+}
+
+/// measure runs f and returns what it allocated on this thread, or `None` if allocations aren't
+/// being counted.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Allocations>) {
+    if !is_counting() {
+        return (f(), None);
+    }
+    let (count, bytes, live, outer_peak) = COUNTERS.with(|c| {
+        let start = (c.count.get(), c.bytes.get(), c.live.get(), c.peak.get());
+        c.peak.set(c.live.get());
+        start
+    });
+    let result = f();
+    let allocations = COUNTERS.with(|c| {
+        let peak = c.peak.get();
+        // Put back the peak of any measurement this one is nested in.
+        c.peak.set(peak.max(outer_peak));
+        Allocations {
+            count: c.count.get() - count,
+            bytes: c.bytes.get() - bytes,
+            peak: (peak - live).max(0) as u64,
+        }
+    });
+    (result, Some(allocations))
+}
+
+/// Profile is what parsing an input and solving each of the parts from it allocated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub parse: Allocations,
+    pub parts: Vec<(Part, Allocations)>,
+}
+
+/// profile parses the input once and solves each of the given parts once, measuring the
+/// allocations of each phase, or returns `None` if allocations aren't being counted.
+pub fn profile(solver: &dyn DynSolver, input: &str, parts: &[Part]) -> Result<Option<Profile>> {
+    let (parsed, parse) = measure(|| solver.parse(input));
+    let (parsed, Some(parse)) = (parsed?, parse) else {
+        return Ok(None);
+    };
+    let mut part_allocations = vec![];
+    for &part in parts {
+        let (answer, allocations) = measure(|| solver.solve(part, parsed.as_ref()));
+        answer?;
+        part_allocations.push((part, allocations.unwrap_or_default()));
+    }
+    Ok(Some(Profile { parse, parts: part_allocations }))
+}
+
+/// format_bytes formats a number of bytes with a binary unit, like `512 B` or `3.4 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod test {
+    use std::hint::black_box;
+
+    use test_case::test_case;
+
+    use super::*;
+
+    use crate::SOLVERS;
+
+    #[test]
+    fn test_measure_counts_allocations() {
+        let (v, allocations) = measure(|| black_box(Vec::<u8>::with_capacity(1000)));
+        assert_eq!(allocations, Some(Allocations { count: 1, bytes: 1000, peak: 1000 }));
+        drop(v);
+
+        let (_, allocations) = measure(|| black_box(1 + 1));
+        assert_eq!(allocations, Some(Allocations::default()));
+    }
+
+    #[test]
+    fn test_measure_peak_is_what_was_live_at_once() {
+        let (_, allocations) = measure(|| {
+            for _ in 0..3 {
+                drop(black_box(vec![0u8; 100]));
+            }
+            let a = black_box(vec![0u8; 200]);
+            let b = black_box(vec![0u8; 300]);
+            drop((a, b));
+        });
+        assert_eq!(allocations, Some(Allocations { count: 5, bytes: 800, peak: 500 }));
+    }
+
+    #[test]
+    fn test_nested_measurements() {
+        let (_, outer) = measure(|| {
+            let big = black_box(vec![0u8; 1000]);
+            drop(big);
+            let (_, inner) = measure(|| drop(black_box(vec![0u8; 10])));
+            assert_eq!(inner, Some(Allocations { count: 1, bytes: 10, peak: 10 }));
+        });
+        assert_eq!(outer, Some(Allocations { count: 2, bytes: 1010, peak: 1000 }));
+    }
+
+    #[test]
+    fn test_profile_every_day() {
+        for registration in SOLVERS {
+            let input = registration.generate(0, 50);
+            let profile = profile(registration.solver().as_ref(), &input, &Part::ALL).unwrap().unwrap();
+            assert!(profile.parse.count > 0, "day {}", registration.day);
+            assert_eq!(profile.parts.len(), 2);
+        }
+    }
+
+    #[test_case(0, "0 B")]
+    #[test_case(1023, "1023 B")]
+    #[test_case(1536, "1.5 KiB")]
+    #[test_case(5 * 1024 * 1024, "5.0 MiB")]
+    fn test_format_bytes(bytes: u64, expected: &str) {
+        assert_eq!(format_bytes(bytes), expected);
+    }
+}
@@ -0,0 +1,155 @@
+use std::fmt::{Display, Formatter};
+
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::utils::grid::{CellChar, Grid};
+
+/// Answer is the result of solving one part of a day.
+///
+/// Answers serialize to the most natural JSON value: integers become numbers, text becomes a
+/// string, and bitmaps become an array with one string per row, using `#` for lit pixels and
+/// `.` for dark ones.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    /// A picture, like the one drawn by the CRT in day 10. Lit pixels are `true`.
+    Bitmap(Grid<bool>),
+}
+
+impl Answer {
+    /// bitmap_from_rows builds a bitmap from rows of `#` and `.` characters.
+    pub fn bitmap_from_rows<S: AsRef<str>>(rows: &[S]) -> Result<Self> {
+        Ok(Answer::Bitmap(Grid::from_lines(rows.iter().map(AsRef::as_ref), bool::from_char)?))
+    }
+
+    /// bitmap_rows returns the rows of a bitmap as `#` and `.` characters.
+    fn bitmap_rows(grid: &Grid<bool>) -> Vec<String> {
+        grid.to_string().lines().map(str::to_string).collect()
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Bitmap(grid) => write!(f, "{}", Answer::bitmap_rows(grid).join("\n")),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Integer(n)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Integer(n.into())
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Answer::Integer(n.into())
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Integer(i64::try_from(n).expect("answers are expected to fit in an i64"))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl From<Grid<bool>> for Answer {
+    fn from(grid: Grid<bool>) -> Self {
+        Answer::Bitmap(grid)
+    }
+}
+
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Integer(n) => serializer.serialize_i64(*n),
+            Answer::Text(s) => serializer.serialize_str(s),
+            Answer::Bitmap(grid) => Answer::bitmap_rows(grid).serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Integer(i64),
+            Text(String),
+            Bitmap(Vec<String>),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Integer(n) => Ok(Answer::Integer(n)),
+            Repr::Text(s) => Ok(Answer::Text(s)),
+            Repr::Bitmap(rows) => {
+                Answer::bitmap_from_rows(&rows).map_err(|e| serde::de::Error::custom(format!("{:#}", e)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(42).to_string(), "42");
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+        let bitmap = Answer::bitmap_from_rows(&["#..", ".##"]).unwrap();
+        assert_eq!(bitmap.to_string(), "#..\n.##");
+    }
+
+    #[test]
+    fn test_equality() {
+        assert_eq!(Answer::from(42u32), Answer::from(42usize));
+        assert_ne!(Answer::from(42), Answer::from("42"));
+        assert_ne!(
+            Answer::bitmap_from_rows(&["#."]).unwrap(),
+            Answer::bitmap_from_rows(&[".#"]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bitmap_from_rows_rejects_bad_input() {
+        assert!(Answer::bitmap_from_rows(&["#.", "#"]).is_err());
+        assert!(Answer::bitmap_from_rows(&["#x"]).is_err());
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let answers = vec![
+            Answer::from(-7),
+            Answer::from("TLNGFGMFN"),
+            Answer::bitmap_from_rows(&["#.#", "..."]).unwrap(),
+        ];
+        let json = serde_json::to_string(&answers).unwrap();
+        assert_eq!(json, r##"[-7,"TLNGFGMFN",["#.#","..."]]"##);
+        let parsed: Vec<Answer> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, answers);
+    }
+}
@@ -0,0 +1,236 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::answer::Answer;
+use crate::inputs::{self, Inputs};
+use crate::solver::{Part, Registration};
+
+/// ExpectedAnswers records the known answers for running one day against one input file. The
+/// input is looked up by name in the inputs directories. A part without a known answer is left out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedAnswers {
+    pub day: u8,
+    pub input: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part1: Option<Answer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part2: Option<Answer>,
+}
+
+impl ExpectedAnswers {
+    pub fn get(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+}
+
+/// load_answers reads the answers file, which holds a JSON array of `ExpectedAnswers`.
+pub fn load_answers(path: impl AsRef<Path>) -> Result<Vec<ExpectedAnswers>> {
+    let path = path.as_ref();
+    let contents =
+        fs::read_to_string(path).wrap_err_with(|| format!("could not read {}", path.display()))?;
+    serde_json::from_str(&contents).wrap_err_with(|| format!("could not parse {}", path.display()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Pass,
+    /// The solver returned a different answer than the one on record.
+    Fail { expected: Answer, actual: Answer },
+    /// The solver returned an answer, but there is nothing on record to compare it to.
+    Missing(Answer),
+    /// The input could not be read or parsed, or the solver failed.
+    Error(String),
+}
+
+impl Status {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+/// Verification is the outcome of checking one part of one day against one input.
+#[derive(Debug, Clone)]
+pub struct Verification {
+    pub day: u8,
+    pub part: Part,
+    pub input: PathBuf,
+    pub status: Status,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// verify runs every registered solver against the inputs listed for its day in `answers`, and
+/// compares the results. Days without any recorded answers run against their default input,
+/// `dayNN.txt`, so they show up as missing rather than being skipped.
+pub fn verify(
+    solvers: &[Registration],
+    answers: &[ExpectedAnswers],
+    inputs: &Inputs,
+) -> Vec<Verification> {
+    let mut verifications = vec![];
+    for registration in solvers {
+        let mut expected: Vec<ExpectedAnswers> =
+            answers.iter().filter(|a| a.day == registration.day).cloned().collect();
+        if expected.is_empty() {
+            expected.push(ExpectedAnswers {
+                day: registration.day,
+                input: inputs::day_input(registration.day),
+                part1: None,
+                part2: None,
+            });
+        }
+        for expected in expected {
+            verifications.extend(verify_input(registration, &expected, inputs, &Part::ALL));
+        }
+    }
+    verifications
+}
+
+/// check runs the solvers against each of the inputs in `expected`, but only for the parts that
+/// have an answer on record. It suits inputs that only apply to one of the parts, such as the
+/// examples from the puzzle descriptions.
+pub fn check(
+    solvers: &[Registration],
+    expected: &[ExpectedAnswers],
+    inputs: &Inputs,
+) -> Vec<Verification> {
+    let mut verifications = vec![];
+    for expected in expected {
+        let parts: Vec<Part> = Part::ALL.into_iter().filter(|&part| expected.get(part).is_some()).collect();
+        let Some(registration) = solvers.iter().find(|r| r.day == expected.day) else {
+            verifications.extend(parts.iter().map(|&part| Verification {
+                day: expected.day,
+                part,
+                input: expected.input.clone(),
+                status: Status::Error(format!("there is no solver for day {}", expected.day)),
+                parse_time: Duration::ZERO,
+                solve_time: Duration::ZERO,
+            }));
+            continue;
+        };
+        verifications.extend(verify_input(registration, expected, inputs, &parts));
+    }
+    verifications
+}
+
+fn verify_input(
+    registration: &Registration,
+    expected: &ExpectedAnswers,
+    inputs: &Inputs,
+    parts: &[Part],
+) -> Vec<Verification> {
+    let verification = |part, status, parse_time, solve_time| Verification {
+        day: registration.day,
+        part,
+        input: expected.input.clone(),
+        status,
+        parse_time,
+        solve_time,
+    };
+
+    let solver = registration.solver();
+    let start = Instant::now();
+    let parsed = inputs
+        .read(&expected.input)
+        .and_then(|input| solver.parse(&input));
+    let parse_time = start.elapsed();
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let status = Status::Error(format!("{:#}", e));
+            return parts
+                .iter()
+                .map(|&part| verification(part, status.clone(), parse_time, Duration::ZERO))
+                .collect();
+        }
+    };
+
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = solver.solve(part, parsed.as_ref());
+            let solve_time = start.elapsed();
+            let status = match (answer, expected.get(part)) {
+                (Err(e), _) => Status::Error(format!("{:#}", e)),
+                (Ok(actual), None) => Status::Missing(actual),
+                (Ok(actual), Some(expected)) if &actual == expected => Status::Pass,
+                (Ok(actual), Some(expected)) => Status::Fail { expected: expected.clone(), actual },
+            };
+            verification(part, status, parse_time, solve_time)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::SOLVERS;
+
+    fn day2_solver() -> &'static [Registration] {
+        let idx = SOLVERS.iter().position(|r| r.day == 2).unwrap();
+        &SOLVERS[idx..=idx]
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let answers: Vec<ExpectedAnswers> = serde_json::from_str(
+            r##"[
+                {"day": 5, "input": "day05.txt", "part1": "CMZ"},
+                {"day": 10, "input": "unit_test/day10.txt", "part1": 13140, "part2": ["#.", ".#"]}
+            ]"##,
+        )
+        .unwrap();
+        assert_eq!(answers[0].get(Part::One), Some(&Answer::from("CMZ")));
+        assert_eq!(answers[0].get(Part::Two), None);
+        assert_eq!(answers[1].get(Part::One), Some(&Answer::from(13140)));
+        assert_eq!(answers[1].get(Part::Two), Some(&Answer::bitmap_from_rows(&["#.", ".#"]).unwrap()));
+    }
+
+    #[test]
+    fn test_checked_in_answers_pass() {
+        let inputs = Inputs::new(None);
+        let answers = load_answers(inputs.find("answers.json").unwrap()).unwrap();
+        let verifications = verify(SOLVERS, &answers, &inputs);
+        for v in &verifications {
+            assert_eq!(v.status, Status::Pass, "day {} part {} on {}", v.day, v.part, v.input.display());
+        }
+        assert_eq!(verifications.len(), SOLVERS.len() * Part::ALL.len());
+    }
+
+    #[test]
+    fn test_verify_reports_failures_and_missing_answers() {
+        let answers = vec![ExpectedAnswers {
+            day: 2,
+            input: PathBuf::from("unit_test/day02.txt"),
+            part1: Some(Answer::from(16)),
+            part2: None,
+        }];
+        let verifications = verify(day2_solver(), &answers, &Inputs::new(None));
+        assert_eq!(
+            verifications[0].status,
+            Status::Fail { expected: Answer::from(16), actual: Answer::from(15) }
+        );
+        assert_eq!(verifications[1].status, Status::Missing(Answer::from(12)));
+    }
+
+    #[test]
+    fn test_verify_reports_unreadable_inputs() {
+        let answers = vec![ExpectedAnswers {
+            day: 2,
+            input: PathBuf::from("does_not_exist.txt"),
+            part1: Some(Answer::from(15)),
+            part2: None,
+        }];
+        let verifications = verify(day2_solver(), &answers, &Inputs::new(None));
+        assert!(verifications.iter().all(|v| v.status.is_failure()));
+    }
+}
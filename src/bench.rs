@@ -0,0 +1,159 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::allocations::{self, Profile};
+use crate::solver::{DynSolver, Part};
+
+/// Stats summarizes the timings of repeated runs of the same thing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// from_samples computes the statistics of a set of timings. There are no statistics for an
+    /// empty set.
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        // The sample standard deviation, since the runs are a sample of all possible runs.
+        let variance = if n > 1 {
+            secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+        Some(Stats {
+            samples: n,
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// BenchOptions controls how many times each phase is run.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    /// Runs that are timed.
+    pub iterations: usize,
+    /// Runs made before timing starts, to warm up caches and the branch predictor.
+    pub warmup: usize,
+}
+
+/// Timings are the statistics for parsing an input and for solving each of the parts from it.
+#[derive(Debug, Clone)]
+pub struct Timings {
+    pub parse: Stats,
+    pub parts: Vec<(Part, Stats)>,
+    /// What each phase allocates, if allocations are being counted. They are measured in a run
+    /// of their own, after the timed runs.
+    pub allocations: Option<Profile>,
+}
+
+/// bench times the parse step and each of the given parts separately. Parts are timed against a
+/// single parsed input, so their timings don't include parsing.
+pub fn bench(solver: &dyn DynSolver, input: &str, parts: &[Part], options: BenchOptions) -> Result<Timings> {
+    if options.iterations == 0 {
+        return Err(eyre!("at least one iteration is needed to collect timings"));
+    }
+    let parse = time(options, || {
+        black_box(solver.parse(black_box(input))).map(drop)
+    })
+    .wrap_err("could not parse the input")?;
+
+    let parsed = solver.parse(input)?;
+    let mut part_stats = vec![];
+    for &part in parts {
+        let stats = time(options, || {
+            black_box(solver.solve(part, black_box(parsed.as_ref()))).map(drop)
+        })
+        .wrap_err_with(|| format!("part {} failed", part))?;
+        part_stats.push((part, stats));
+    }
+    let allocations = allocations::profile(solver, input, parts)?;
+    Ok(Timings { parse, parts: part_stats, allocations })
+}
+
+/// time runs f the number of times asked for by the options, and returns the statistics of the
+/// timed runs. It stops at the first error.
+fn time(options: BenchOptions, mut f: impl FnMut() -> Result<()>) -> Result<Stats> {
+    for _ in 0..options.warmup {
+        f()?;
+    }
+    let mut samples = Vec::with_capacity(options.iterations);
+    for _ in 0..options.iterations {
+        let start = Instant::now();
+        f()?;
+        samples.push(start.elapsed());
+    }
+    Stats::from_samples(&samples).ok_or_else(|| eyre!("no timings were collected"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::SOLVERS;
+
+    fn millis(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&millis(&[4, 2, 8, 6])).unwrap();
+        assert_eq!(stats.samples, 4);
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        // The sample variance is (9 + 1 + 1 + 9) / 3 ms².
+        assert_eq!(stats.stddev.as_micros(), 2581);
+    }
+
+    #[test]
+    fn test_stats_of_a_single_sample() {
+        let stats = Stats::from_samples(&millis(&[3])).unwrap();
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.stddev, Duration::ZERO);
+        assert!(Stats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn test_bench_times_every_implementation() {
+        let registration = SOLVERS.iter().find(|r| r.day == 6).unwrap();
+        let options = BenchOptions { iterations: 3, warmup: 1 };
+        for implementation in registration.implementations() {
+            let timings =
+                bench(implementation.solver.as_ref(), "mjqjpqmgbljsphdztnvjfqwrcgsmlb", &Part::ALL, options).unwrap();
+            assert_eq!(timings.parse.samples, 3);
+            assert_eq!(timings.parts.len(), 2);
+            // The tests run with the counting allocator.
+            assert_eq!(timings.allocations.unwrap().parts.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_bench_reports_solver_errors() {
+        let registration = SOLVERS.iter().find(|r| r.day == 6).unwrap();
+        let options = BenchOptions { iterations: 3, warmup: 0 };
+        assert!(bench(registration.solver().as_ref(), "abc", &Part::ALL, options).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use rust_aoc2022::generate::DEFAULT_SIZE;
+use rust_aoc2022::output::Format;
+use rust_aoc2022::solver::Part;
+
+/// Solutions to Advent of Code 2022.
+#[derive(Debug, Parser)]
+#[command(name = "rust-aoc2022", version)]
+pub struct Cli {
+    /// Look for puzzle inputs in DIR before $AOC_INPUTS and the inputs directory of this crate.
+    #[arg(long, global = true, value_name = "DIR")]
+    pub inputs: Option<PathBuf>,
+    /// What to log to stderr: a level, optionally followed by levels for modules, such as
+    /// `info` or `warn,day07=debug`. Defaults to $AOC_LOG, or `warn`.
+    #[arg(long, global = true, value_name = "FILTER")]
+    pub log: Option<String>,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the solver for a single day.
+    Run {
+        /// Day of the calendar, from 1 to 25.
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Only run the given part (1 or 2). Both parts run by default.
+        #[arg(long)]
+        part: Option<Part>,
+        /// Read the puzzle input from PATH instead of the default input file. Use - for stdin.
+        #[arg(long, value_name = "PATH", conflicts_with = "example")]
+        input: Option<PathBuf>,
+        /// Use the example input from the puzzle description.
+        #[arg(long)]
+        example: bool,
+        /// Play the simulation in the terminal before printing the answers. Only some days can
+        /// be visualized.
+        #[arg(long)]
+        visualize: bool,
+        /// How many frames of the visualization to show per second.
+        #[arg(long, default_value_t = 20, requires = "visualize", value_parser = clap::value_parser!(u32).range(1..))]
+        fps: u32,
+        /// Write the frames of the visualization to PATH as text, instead of playing them.
+        #[arg(long, value_name = "PATH", requires = "visualize")]
+        frames: Option<PathBuf>,
+        /// Print the answers as text, as a JSON array (json) or as one JSON object per line
+        /// (jsonl), with their timings.
+        #[arg(long, default_value_t = Format::Text)]
+        format: Format,
+        /// Report what parsing and each part allocate on the heap. The binary must be built with
+        /// the count-allocations feature.
+        #[arg(long, conflicts_with = "format")]
+        allocations: bool,
+    },
+    /// Time a day repeatedly, reporting statistics for parsing and for each part.
+    Bench {
+        /// Day of the calendar, from 1 to 25.
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Only time the given part (1 or 2). Both parts are timed by default.
+        #[arg(long)]
+        part: Option<Part>,
+        /// Only time the implementation with the given name. Every implementation is timed by
+        /// default.
+        #[arg(long = "impl", value_name = "NAME")]
+        implementation: Option<String>,
+        /// How many timed runs to make of each phase.
+        #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u64).range(1..))]
+        iterations: u64,
+        /// How many untimed runs to make before timing starts.
+        #[arg(long, default_value_t = 3)]
+        warmup: u64,
+        /// Read the puzzle input from PATH instead of the default input file. Use - for stdin.
+        #[arg(long, value_name = "PATH", conflicts_with = "example")]
+        input: Option<PathBuf>,
+        /// Use the example input from the puzzle description.
+        #[arg(long)]
+        example: bool,
+    },
+    /// Run both parts of every registered day against its real input, in parallel.
+    RunAll {
+        /// How many days to run at the same time. Defaults to the number of CPUs.
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        jobs: Option<u64>,
+        /// Give up on a day that takes longer than this many seconds.
+        #[arg(long, value_name = "SECONDS", default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: u64,
+        /// Print the results as a table, as a JSON array (json) or as one JSON object per line
+        /// (jsonl).
+        #[arg(long, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// List the registered days.
+    List,
+    /// Load a day's input and explore it interactively, with commands read from stdin. Type
+    /// help for the commands of the day.
+    Repl {
+        /// Day of the calendar, from 1 to 25.
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Read the puzzle input from PATH instead of the default input file.
+        #[arg(long, value_name = "PATH", conflicts_with = "example")]
+        input: Option<PathBuf>,
+        /// Use the example input from the puzzle description.
+        #[arg(long)]
+        example: bool,
+    },
+    /// Check that every implementation of a day gives the same answers, on the real input, the
+    /// examples and generated inputs. Disagreements are shrunk to a small input that shows them.
+    Compare {
+        /// Day of the calendar, from 1 to 25. Every day with more than one implementation is
+        /// compared by default.
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+        /// How many generated inputs to compare on.
+        #[arg(long, default_value_t = 20)]
+        seeds: u64,
+        /// The size of the generated inputs.
+        #[arg(long, default_value_t = 100)]
+        size: usize,
+    },
+    /// Write a random input for a day, for stress testing and benchmarking.
+    Generate {
+        /// Day of the calendar, from 1 to 25.
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// How big the input is. What it counts depends on the day, such as lines or moves.
+        #[arg(long, default_value_t = DEFAULT_SIZE)]
+        size: usize,
+        /// The seed to generate the input from. A random seed is used, and reported on stderr,
+        /// if none is given.
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Write the input to PATH instead of stdout.
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Check every registered day against the answers on record, reporting any regressions.
+    Verify {
+        /// The answers file to check against. Defaults to answers.json in the inputs directory.
+        #[arg(long, value_name = "PATH", conflicts_with = "examples")]
+        answers: Option<PathBuf>,
+        /// Check the examples from the puzzle descriptions instead of the real inputs.
+        #[arg(long)]
+        examples: bool,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_cli_is_well_formed() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_run() {
+        let cli = Cli::try_parse_from(["aoc", "run", "5", "--part", "2", "--input", "-"]).unwrap();
+        let Command::Run { day, part, input, example, visualize, format, .. } = cli.command else {
+            panic!("expected the run command");
+        };
+        assert_eq!(day, 5);
+        assert_eq!(part, Some(Part::Two));
+        assert_eq!(input, Some(PathBuf::from("-")));
+        assert!(!example);
+        assert!(!visualize);
+        assert_eq!(format, Format::Text);
+        let cli = Cli::try_parse_from(["aoc", "run", "5", "--format", "json"]).unwrap();
+        assert!(matches!(cli.command, Command::Run { format: Format::Json, .. }));
+        let cli = Cli::try_parse_from(["aoc", "run", "5", "--allocations"]).unwrap();
+        assert!(matches!(cli.command, Command::Run { allocations: true, .. }));
+        assert!(Cli::try_parse_from(["aoc", "run", "5", "--allocations", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_run_visualize() {
+        let cli = Cli::try_parse_from(["aoc", "run", "9", "--visualize", "--fps", "5"]).unwrap();
+        let Command::Run { visualize, fps, frames, .. } = cli.command else {
+            panic!("expected the run command");
+        };
+        assert!(visualize);
+        assert_eq!(fps, 5);
+        assert_eq!(frames, None);
+        assert!(Cli::try_parse_from(["aoc", "run", "9", "--frames", "out.txt"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "run", "9", "--visualize", "--fps", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_repl() {
+        let cli = Cli::try_parse_from(["aoc", "repl", "7", "--example"]).unwrap();
+        let Command::Repl { day, input, example } = cli.command else {
+            panic!("expected the repl command");
+        };
+        assert_eq!(day, 7);
+        assert_eq!(input, None);
+        assert!(example);
+        assert!(Cli::try_parse_from(["aoc", "repl"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        let cli = Cli::try_parse_from(["aoc", "bench", "6", "--impl", "hashmap", "--iterations", "10"]).unwrap();
+        let Command::Bench { day, part, implementation, iterations, warmup, .. } = cli.command else {
+            panic!("expected the bench command");
+        };
+        assert_eq!(day, 6);
+        assert_eq!(part, None);
+        assert_eq!(implementation.as_deref(), Some("hashmap"));
+        assert_eq!(iterations, 10);
+        assert_eq!(warmup, 3);
+        assert!(Cli::try_parse_from(["aoc", "bench", "6", "--iterations", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_run_all() {
+        let cli = Cli::try_parse_from(["aoc", "run-all", "--jobs", "2"]).unwrap();
+        let Command::RunAll { jobs, timeout, format } = cli.command else {
+            panic!("expected the run-all command");
+        };
+        assert_eq!(jobs, Some(2));
+        assert_eq!(timeout, 60);
+        assert_eq!(format, Format::Text);
+        let cli = Cli::try_parse_from(["aoc", "run-all", "--format", "jsonl"]).unwrap();
+        assert!(matches!(cli.command, Command::RunAll { format: Format::Jsonl, .. }));
+        assert!(Cli::try_parse_from(["aoc", "run-all", "--format", "yaml"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "run-all", "--jobs", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        let cli = Cli::try_parse_from(["aoc", "generate", "7", "--seed", "42"]).unwrap();
+        let Command::Generate { day, size, seed, output } = cli.command else {
+            panic!("expected the generate command");
+        };
+        assert_eq!(day, 7);
+        assert_eq!(size, DEFAULT_SIZE);
+        assert_eq!(seed, Some(42));
+        assert_eq!(output, None);
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        let cli = Cli::try_parse_from(["aoc", "compare", "--seeds", "5"]).unwrap();
+        let Command::Compare { day, seeds, size } = cli.command else {
+            panic!("expected the compare command");
+        };
+        assert_eq!(day, None);
+        assert_eq!(seeds, 5);
+        assert_eq!(size, 100);
+    }
+
+    #[test]
+    fn test_parse_inputs_anywhere() {
+        let cli = Cli::try_parse_from(["aoc", "run-all", "--inputs", "/tmp/inputs"]).unwrap();
+        assert_eq!(cli.inputs, Some(PathBuf::from("/tmp/inputs")));
+        let cli = Cli::try_parse_from(["aoc", "--inputs", "/tmp/inputs", "list"]).unwrap();
+        assert_eq!(cli.inputs, Some(PathBuf::from("/tmp/inputs")));
+        let cli = Cli::try_parse_from(["aoc", "run", "7", "--log", "day07=debug"]).unwrap();
+        assert_eq!(cli.log.as_deref(), Some("day07=debug"));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_part() {
+        assert!(Cli::try_parse_from(["aoc", "run", "5", "--part", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_day() {
+        assert!(Cli::try_parse_from(["aoc", "run", "26"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "run", "one"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_input_with_example() {
+        assert!(Cli::try_parse_from(["aoc", "run", "5", "--input", "x", "--example"]).is_err());
+    }
+}
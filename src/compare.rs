@@ -0,0 +1,316 @@
+//! Differential testing of the implementations of a day.
+//!
+//! Every implementation registered for a day (see `Solver::alternatives`) must give the same
+//! answers. `compare` runs them all against the real input, the examples and generated inputs,
+//! and when they disagree it shrinks the input down to a small one that still shows the
+//! disagreement, which is much easier to debug than a real input.
+
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+use color_eyre::eyre::Result;
+
+use crate::answer::Answer;
+use crate::examples;
+use crate::inputs::{self, Inputs};
+use crate::solver::{Implementation, Part, Registration};
+
+/// CompareOptions controls which generated inputs the implementations are compared on.
+#[derive(Debug, Clone, Copy)]
+pub struct CompareOptions {
+    /// Generated inputs are made from the seeds `0..seeds`.
+    pub seeds: u64,
+    /// The size of the generated inputs. Small inputs shrink faster.
+    pub size: usize,
+}
+
+/// Outcome is what one implementation made of an input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Solved(Answer),
+    /// The input could not be parsed, or the solver returned an error.
+    Error(String),
+    Panicked(String),
+}
+
+impl Outcome {
+    /// agrees is true if two outcomes are the same answer, or both the same kind of failure.
+    /// Error messages aren't compared, since implementations are free to word them differently.
+    fn agrees(&self, other: &Outcome) -> bool {
+        match (self, other) {
+            (Outcome::Solved(a), Outcome::Solved(b)) => a == b,
+            (Outcome::Error(_), Outcome::Error(_)) | (Outcome::Panicked(_), Outcome::Panicked(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Solved(answer) => write!(f, "{}", answer),
+            Outcome::Error(e) => write!(f, "error: {}", e),
+            Outcome::Panicked(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+/// Case is an input the implementations are compared on, and where it came from.
+#[derive(Debug, Clone)]
+pub struct Case {
+    pub source: String,
+    pub input: String,
+}
+
+/// Disagreement is an input on which the implementations of a day gave different outcomes for
+/// a part. The input is the shrunk one, not the case it was found with.
+#[derive(Debug, Clone)]
+pub struct Disagreement {
+    pub day: u8,
+    pub part: Part,
+    pub source: String,
+    pub input: String,
+    pub outcomes: Vec<(&'static str, Outcome)>,
+}
+
+/// Report is the result of comparing the implementations of a day.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub implementations: Vec<&'static str>,
+    pub cases: usize,
+    pub disagreements: Vec<Disagreement>,
+}
+
+/// The most times `shrink` runs the implementations before settling for what it has.
+const MAX_SHRINK_RUNS: usize = 5000;
+
+/// compare runs every implementation of a day on its real input, if there is one, its examples
+/// and generated inputs. Each part reports at most one disagreement, for the first case that
+/// shows it.
+pub fn compare(registration: &Registration, inputs: &Inputs, options: CompareOptions) -> Result<Report> {
+    let implementations = registration.implementations();
+    let cases = cases(registration, inputs, options)?;
+    let mut disagreements = vec![];
+    for part in Part::ALL {
+        for case in &cases {
+            if disagree(&implementations, part, &case.input).is_none() {
+                continue;
+            }
+            let input = shrink(&implementations, part, &case.input);
+            let outcomes = disagree(&implementations, part, &input).expect("shrinking keeps the disagreement");
+            disagreements.push(Disagreement {
+                day: registration.day,
+                part,
+                source: case.source.clone(),
+                input,
+                outcomes,
+            });
+            break;
+        }
+    }
+    Ok(Report {
+        implementations: implementations.iter().map(|i| i.name).collect(),
+        cases: cases.len(),
+        disagreements,
+    })
+}
+
+/// cases collects the inputs to compare a day's implementations on.
+pub fn cases(registration: &Registration, inputs: &Inputs, options: CompareOptions) -> Result<Vec<Case>> {
+    let day = registration.day;
+    let mut cases = vec![];
+    if let Ok(input) = inputs.read(inputs::day_input(day)) {
+        cases.push(Case { source: inputs::day_input(day).display().to_string(), input });
+    }
+    for example in examples::discover(inputs)?.into_iter().filter(|e| e.day == day) {
+        let input = inputs.read(&example.input)?;
+        cases.push(Case { source: example.input.display().to_string(), input });
+    }
+    for seed in 0..options.seeds {
+        cases.push(Case {
+            source: format!("generated (seed {}, size {})", seed, options.size),
+            input: registration.generate(seed, options.size),
+        });
+    }
+    Ok(cases)
+}
+
+/// disagree runs every implementation on the input and returns their outcomes, if any two of
+/// them disagree.
+pub fn disagree(implementations: &[Implementation], part: Part, input: &str) -> Option<Vec<(&'static str, Outcome)>> {
+    let outcomes: Vec<(&'static str, Outcome)> = implementations.iter().map(|i| (i.name, run(i, part, input))).collect();
+    let (_, first) = outcomes.first()?;
+    if outcomes.iter().all(|(_, outcome)| outcome.agrees(first)) {
+        return None;
+    }
+    Some(outcomes)
+}
+
+fn run(implementation: &Implementation, part: Part, input: &str) -> Outcome {
+    match catch_quietly(|| implementation.solver.run(part, input)) {
+        Ok(Ok(answer)) => Outcome::Solved(answer),
+        Ok(Err(e)) => Outcome::Error(format!("{:#}", e)),
+        Err(payload) => Outcome::Panicked(
+            payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "the solver panicked".to_string()),
+        ),
+    }
+}
+
+/// shrink returns the smallest input it can find on which the implementations still disagree.
+/// It removes whole lines first, then characters, in chunks that halve in size, until neither
+/// gets the input any smaller.
+pub fn shrink(implementations: &[Implementation], part: Part, input: &str) -> String {
+    let mut runs = 0;
+    let mut still_disagrees = |candidate: &str| {
+        runs += 1;
+        runs <= MAX_SHRINK_RUNS && disagree(implementations, part, candidate).is_some()
+    };
+    let mut current = input.to_string();
+    loop {
+        let before = current.len();
+        let lines: Vec<String> = current.split_inclusive('\n').map(str::to_string).collect();
+        current = remove_chunks(lines, &mut still_disagrees);
+        let chars: Vec<String> = current.chars().map(String::from).collect();
+        current = remove_chunks(chars, &mut still_disagrees);
+        if current.len() == before {
+            return current;
+        }
+    }
+}
+
+/// remove_chunks tries removing runs of units, starting with half of them and going down to
+/// one at a time, keeping every removal after which the input is still interesting.
+fn remove_chunks(mut units: Vec<String>, interesting: &mut impl FnMut(&str) -> bool) -> String {
+    let mut chunk = units.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < units.len() {
+            let end = (start + chunk).min(units.len());
+            let candidate: String = units[..start].iter().chain(&units[end..]).map(String::as_str).collect();
+            if interesting(&candidate) {
+                units.drain(start..end);
+            } else {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+    units.concat()
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// catch_quietly runs f, catching a panic without printing it. Panics are reported as outcomes,
+/// and shrinking can cause a lot of them. Panics anywhere else still go to the panic hook that
+/// was installed before, which is only skipped on this thread while f runs.
+fn catch_quietly<T>(f: impl FnOnce() -> T) -> std::thread::Result<T> {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(Cell::get) {
+                previous(info)
+            }
+        }));
+    });
+    let was_quiet = QUIET.with(|quiet| quiet.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    QUIET.with(|quiet| quiet.set(was_quiet));
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::solver::{DynSolver, Solver};
+    use crate::SOLVERS;
+
+    /// CountingSolver counts the a's in the input, in part 1, and the lines, in part 2. The
+    /// sloppy version gets part 1 wrong when an a follows a b.
+    #[derive(Default)]
+    struct CountingSolver {
+        sloppy: bool,
+    }
+
+    impl Solver for CountingSolver {
+        type Input = String;
+
+        fn parse(&self, input: &str) -> Result<Self::Input> {
+            Ok(input.to_string())
+        }
+
+        fn part1(&self, input: &Self::Input) -> Result<Answer> {
+            let count = input.matches('a').count();
+            if self.sloppy && input.contains("ba") {
+                return Ok((count + 1).into());
+            }
+            Ok(count.into())
+        }
+
+        fn part2(&self, input: &Self::Input) -> Result<Answer> {
+            Ok(input.lines().count().into())
+        }
+    }
+
+    #[test]
+    fn test_catch_quietly_only_silences_its_own_panics() {
+        let result = catch_quietly(|| -> u8 { panic!("quiet") });
+        assert_eq!(result.unwrap_err().downcast_ref::<&str>(), Some(&"quiet"));
+        assert!(!QUIET.with(Cell::get), "panics after catch_quietly must reach the previous hook");
+
+        let nested = catch_quietly(|| {
+            let inner = catch_quietly(|| -> u8 { panic!("inner") });
+            (inner.is_err(), QUIET.with(Cell::get))
+        });
+        assert_eq!(nested.unwrap(), (true, true));
+        assert!(!QUIET.with(Cell::get));
+    }
+
+    fn implementations() -> Vec<Implementation> {
+        let solver = |sloppy| -> Box<dyn DynSolver> { Box::new(CountingSolver { sloppy }) };
+        vec![
+            Implementation { name: "default", solver: solver(false) },
+            Implementation { name: "sloppy", solver: solver(true) },
+        ]
+    }
+
+    #[test]
+    fn test_disagree() {
+        let implementations = implementations();
+        assert!(disagree(&implementations, Part::One, "abab\n").is_some());
+        assert!(disagree(&implementations, Part::One, "aabb\n").is_none());
+        assert!(disagree(&implementations, Part::Two, "abab\n").is_none());
+    }
+
+    #[test]
+    fn test_shrink_finds_the_smallest_input() {
+        let input = "xyz\nqqq abba q\nfoo\nbar\n";
+        assert_eq!(shrink(&implementations(), Part::One, input), "ba");
+    }
+
+    #[test]
+    fn test_outcomes_agree_on_the_kind_of_failure() {
+        assert!(Outcome::Error("a".to_string()).agrees(&Outcome::Error("b".to_string())));
+        assert!(!Outcome::Error("a".to_string()).agrees(&Outcome::Panicked("a".to_string())));
+        assert!(!Outcome::Solved(1.into()).agrees(&Outcome::Solved(2.into())));
+    }
+
+    #[test]
+    fn test_every_day_agrees_with_itself() {
+        let options = CompareOptions { seeds: 5, size: 100 };
+        for registration in SOLVERS {
+            let report = compare(registration, &Inputs::new(None), options).unwrap();
+            assert!(report.cases > 5, "day {}", registration.day);
+            assert!(report.disagreements.is_empty(), "day {}: {:?}", registration.day, report.disagreements);
+        }
+    }
+}
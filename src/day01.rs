@@ -1,40 +1,67 @@
+use color_eyre::eyre::Result;
+
+use crate::answer::Answer;
+use crate::generate::Generator;
 use crate::solver;
+use crate::utils::parse::{lines, parse_all, sections, unsigned};
+use crate::utils::rng::Rng;
 
+#[derive(Default)]
 pub struct Day1Solver {}
 
 impl solver::Solver for Day1Solver {
-    fn solve_part_1(&self, lines: Vec<String>) -> String {
-        let mut max_val: u32 = 0;
-        let mut current: u32 = 0;
-        for line in lines {
-            if line.is_empty() {
-                if current > max_val {
-                    max_val = current;
-                }
-                current = 0;
-                continue;
-            }
-            current += line.parse::<u32>().unwrap();
-        }
-        if current > max_val {
-            max_val = current;
-        }
-        max_val.to_string()
+    /// The total number of calories carried by each elf.
+    type Input = Vec<u32>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        // Each elf's snacks are listed one per line, and the elves are separated by blank lines.
+        let elves: Vec<Vec<u32>> = parse_all(input, sections(lines(unsigned)))?;
+        log::debug!("{} elves", elves.len());
+        Ok(elves.iter().map(|snacks| snacks.iter().sum()).collect())
+    }
+
+    fn part1(&self, calories: &Self::Input) -> Result<Answer> {
+        let max_val = calories.iter().copied().max().unwrap_or(0);
+        Ok(max_val.into())
+    }
+
+    fn part2(&self, calories: &Self::Input) -> Result<Answer> {
+        let mut calories = calories.clone();
+        // Sort from the most to the fewest calories so we can grab the top three elves.
+        calories.sort_by(|a, b| b.cmp(a));
+        Ok(calories.iter().take(3).sum::<u32>().into())
+    }
+}
+
+impl Generator for Day1Solver {
+    /// The size is the number of elves.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let elves: Vec<String> = (0..size.max(1))
+            .map(|_| {
+                let snacks: Vec<String> = (0..rng.range(1..15)).map(|_| rng.range(1000..70000).to_string()).collect();
+                snacks.join("\n")
+            })
+            .collect();
+        elves.join("\n\n") + "\n"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::solver::Solver;
+
+    #[test]
+    fn test_parse_totals_each_elf() {
+        let solver = Day1Solver {};
+        assert_eq!(solver.parse("1000\n2000\n\n4000\n\n\n5000\n6000\n").unwrap(), vec![3000, 4000, 11000]);
     }
 
-    fn solve_part_2(&self, lines: Vec<String>) -> String {
-        let mut calories: Vec<u32> = vec![];
-        let mut current: u32 = 0;
-        for line in lines {
-            if line.is_empty() {
-                calories.push(current);
-                current = 0;
-                continue;
-            }
-            current += line.parse::<u32>().unwrap();
-        }
-        calories.sort();
-        // string with greeting for ssh login
-        calories.iter().take(3).sum::<u32>().to_string()
+    #[test]
+    fn test_bad_calorie_count() {
+        let solver = Day1Solver {};
+        let err = solver.parse("1000\n\n20x0\n").unwrap_err().to_string();
+        assert!(err.starts_with("line 3, column 3: expected the end of the line"), "{err}");
     }
 }
@@ -1,26 +1,49 @@
 use std::str::FromStr;
 
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::answer::Answer;
+use crate::generate::Generator;
 use crate::solver::Solver;
+use crate::utils::rng::Rng;
 
+#[derive(Default)]
 pub struct Day2Solver {}
 
 impl Solver for Day2Solver {
-    fn solve_part_1(&self, lines: Vec<String>) -> String {
+    /// Each round read both as part 1 and as part 2 describe it.
+    type Input = Vec<(Part1Round, Part2Round)>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let rounds: Vec<_> = input
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| {
+                let part1 = Part1Round::from_str(line);
+                let part2 = Part2Round::from_str(line);
+                part1
+                    .and_then(|part1| Ok((part1, part2?)))
+                    .wrap_err_with(|| format!("line {}: invalid round {:?}", idx + 1, line))
+            })
+            .collect::<Result<_>>()?;
+        log::debug!("{} rounds", rounds.len());
+        Ok(rounds)
+    }
+
+    fn part1(&self, rounds: &Self::Input) -> Result<Answer> {
         let mut your_score = 0u32;
-        for line in lines {
-            let round = Part1Round::from_str(&line).unwrap();
+        for (round, _) in rounds {
             your_score += round.you.outcome(round.opponent).score() + round.you.score();
         }
-        your_score.to_string()
+        Ok(your_score.into())
     }
 
-    fn solve_part_2(&self, lines: Vec<String>) -> String {
+    fn part2(&self, rounds: &Self::Input) -> Result<Answer> {
         let mut your_score = 0u32;
-        for line in lines {
+        for (_, round) in rounds {
             // At this point we've fully parsed a round. Since we have the desired outcome
             // we know the points we should get for the match -- now we just need to figure
             // out what move we should throw.
-            let round = Part2Round::from_str(&line).unwrap();
             your_score += round.desired_outcome.score();
             let desired_move = match round.desired_outcome {
                 Outcome::Win => round.opponent.get_losing_choice(),
@@ -29,14 +52,23 @@ impl Solver for Day2Solver {
             };
             your_score += desired_move.score();
         }
-        your_score.to_string()
+        Ok(your_score.into())
+    }
+}
+
+impl Generator for Day2Solver {
+    /// The size is the number of rounds.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1))
+            .map(|_| format!("{} {}\n", rng.pick(&['A', 'B', 'C']), rng.pick(&['X', 'Y', 'Z'])))
+            .collect()
     }
 }
 
 // Part1Round encodes the information in part 1: What moves you and your opponent
 // will do.
 #[derive(Debug, Clone, Copy)]
-struct Part1Round {
+pub struct Part1Round {
     you: Choice,
     opponent: Choice,
 }
@@ -50,7 +82,7 @@ impl FromStr for Part1Round {
         // something really simple where the string format is very simple consuming char one by one
         // is enough.
         let (Some(opponent), Some(' '), Some(you), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
-            return Err(color_eyre::eyre::eyre!("bad"));
+            return Err(color_eyre::eyre::eyre!("expected two moves separated by a space"));
         };
         Ok(Self {
             you: you.try_into()?,
@@ -60,7 +92,7 @@ impl FromStr for Part1Round {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Part2Round {
+pub struct Part2Round {
     desired_outcome: Outcome,
     opponent: Choice,
 }
@@ -74,7 +106,7 @@ impl FromStr for Part2Round {
         // something really simple where the string format is very simple consuming char one by one
         // is enough.
         let (Some(opponent), Some(' '), Some(desired_outcome), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
-            return Err(color_eyre::eyre::eyre!("bad"));
+            return Err(color_eyre::eyre::eyre!("expected a move and an outcome separated by a space"));
         };
         Ok(Self {
             desired_outcome: desired_outcome.try_into()?,
@@ -191,19 +223,10 @@ impl TryFrom<char> for Outcome {
 mod test {
     use super::*;
 
-    use crate::lines_from_file;
-
-    #[test]
-    fn test_part_1() {
-        let solver = Day2Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day02.txt");
-        assert_eq!(solver.solve_part_1(lines), "15");
-    }
-
     #[test]
-    fn test_part_2() {
+    fn test_bad_line_reports_line_number() {
         let solver = Day2Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day02.txt");
-        assert_eq!(solver.solve_part_2(lines), "12");
+        let err = solver.parse("A Y\nB Q\n").unwrap_err();
+        assert!(format!("{err:?}").contains("line 2"));
     }
 }
@@ -1,3 +1,4 @@
+use color_eyre::eyre::{eyre, Result, WrapErr};
 use itertools::Itertools;
 use std::{
     collections::{HashMap, HashSet},
@@ -5,52 +6,96 @@ use std::{
     str::FromStr,
 };
 
+use crate::answer::Answer;
+use crate::generate::Generator;
 use crate::solver::Solver;
+use crate::utils::rng::Rng;
 
+#[derive(Default)]
 pub struct Day3Solver {}
 
 impl Solver for Day3Solver {
-    fn solve_part_1(&self, lines: Vec<String>) -> String {
+    type Input = Vec<Rucksack>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let rucksacks: Vec<_> = input
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(idx, l)| {
+                Rucksack::from_str(l.trim())
+                    .wrap_err_with(|| format!("line {}: invalid rucksack {:?}", idx + 1, l))
+            })
+            .collect::<Result<_>>()?;
+        log::debug!("{} rucksacks", rucksacks.len());
+        Ok(rucksacks)
+    }
+
+    fn part1(&self, rucksacks: &Self::Input) -> Result<Answer> {
         let mut priority = 0usize;
-        for line in lines {
-            let line = line.trim();
-            let sack = Rucksack::from_str(line).unwrap();
+        for sack in rucksacks {
             if let Some(k) = sack.find_common_item() {
                 let item_priority = k.priority();
                 priority += item_priority;
                 continue;
             }
         }
-        priority.to_string()
+        Ok(priority.into())
     }
 
-    fn solve_part_2(&self, lines: Vec<String>) -> String {
-        let mut lines = lines;
-        lines.retain(|l| !l.trim().is_empty());
-
-        let rucksacks: Vec<Result<Rucksack, _>> =
-            lines.iter().map(|l| Rucksack::from_str(l.trim())).collect();
+    fn part2(&self, rucksacks: &Self::Input) -> Result<Answer> {
+        if rucksacks.len() % 3 != 0 {
+            return Err(eyre!(
+                "expected the rucksacks to come in groups of three, found {} rucksacks",
+                rucksacks.len()
+            ));
+        }
         // Grab the rucksacks, grab three at a time, and find the common element in all of them.
-        let sum = itertools::process_results(rucksacks, |rs| {
-            Itertools::tuples(rs)
-                .map(|(a, b, c)| {
-                    a.keys()
-                        .iter()
-                        .copied()
-                        .find(|i| b.keys().contains(i) && c.keys().contains(i))
-                        .map(|i| i.priority())
-                        .unwrap()
-                })
-                .sum::<usize>()
-        })
-        .unwrap();
-        sum.to_string()
+        let mut sum = 0usize;
+        for (group, (a, b, c)) in rucksacks.iter().tuples().enumerate() {
+            let badge = a
+                .keys()
+                .iter()
+                .copied()
+                .find(|i| b.keys().contains(i) && c.keys().contains(i))
+                .ok_or_else(|| eyre!("group {}: the rucksacks have no item in common", group + 1))?;
+            sum += badge.priority();
+        }
+        Ok(sum.into())
+    }
+}
+
+impl Generator for Day3Solver {
+    /// The size is the number of rucksacks, rounded up to a whole number of groups of three.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+        let mut output = String::new();
+        for _ in 0..size.max(1).div_ceil(3) {
+            let mut items = items.clone();
+            rng.shuffle(&mut items);
+            // The badge is the only item the group shares. Each elf then gets 17 items of their
+            // own: one that goes in both compartments, and 8 for each compartment.
+            let (badge, items) = items.split_first().expect("there are 52 items");
+            for own in items.chunks(17) {
+                let (common, own) = own.split_first().expect("every elf gets 17 items");
+                let (first_only, second_only) = own.split_at(8);
+                let length = rng.range(2..16);
+                let mut first: Vec<char> = (0..length - 2).map(|_| *rng.pick(first_only)).collect();
+                first.extend([*common, *badge]);
+                let mut second: Vec<char> = (0..length - 1).map(|_| *rng.pick(second_only)).collect();
+                second.push(*common);
+                rng.shuffle(&mut first);
+                rng.shuffle(&mut second);
+                output.extend(first.into_iter().chain(second));
+                output.push('\n');
+            }
+        }
+        output
     }
 }
 
+/// Item is a letter from a-z or A-Z, which `Rucksack::from_str` checks before making one.
 #[derive(Hash, Eq, PartialEq, Debug, Copy, Clone)]
-// TODO: We could add an init that verifies that only a-zA-Z are allowed,
-// but for the purpose of this exercise I chose to ignore that requirement.
 struct Item(char);
 
 impl Item {
@@ -76,7 +121,7 @@ impl fmt::Display for Item {
 }
 
 #[derive(Debug, Clone)]
-struct Rucksack {
+pub struct Rucksack {
     first_compartment: HashMap<Item, usize>,
     second_compartment: HashMap<Item, usize>,
 }
@@ -85,6 +130,11 @@ impl FromStr for Rucksack {
     type Err = color_eyre::Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Items are ASCII letters, so once that is checked every character is a byte and the
+        // line can be split in half by bytes.
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(eyre!("expected only the letters a-z and A-Z, found {:?}", c));
+        }
         let length = s.len();
         if length % 2 == 1 {
             return Err(color_eyre::eyre::eyre!(
@@ -128,35 +178,31 @@ impl Rucksack {
 
 #[cfg(test)]
 mod test {
+    use test_case::test_case;
+
     use super::*;
 
-    use crate::lines_from_file;
-
-    #[test]
-    fn test_part_1() {
-        let solver = Day3Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day03.txt");
-        assert_eq!(solver.solve_part_1(lines), "157");
-    }
+    use crate::inputs::read_input;
 
     #[test]
     fn test_part_1_full() {
         let solver = Day3Solver {};
-        let lines = lines_from_file("./inputs/day03.txt");
-        assert_eq!(solver.solve_part_1(lines), "8109");
+        let input = solver.parse(&read_input("day03.txt").unwrap()).unwrap();
+        assert_eq!(solver.part1(&input).unwrap(), 8109.into());
     }
 
     #[test]
-    fn test_part_2() {
+    fn test_part_2_full() {
         let solver = Day3Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day03.txt");
-        assert_eq!(solver.solve_part_2(lines), "70");
+        let input = solver.parse(&read_input("day03.txt").unwrap()).unwrap();
+        assert_eq!(solver.part2(&input).unwrap(), 2738.into());
     }
 
-    #[test]
-    fn test_part_2_full() {
-        let solver = Day3Solver {};
-        let lines = lines_from_file("./inputs/day03.txt");
-        assert_eq!(solver.solve_part_2(lines), "2738");
+    #[test_case("vJrwpWtwJgWr\nab1c1d\n", "line 2: invalid rucksack \"ab1c1d\": expected only the letters a-z and A-Z, found '1'"; "digit")]
+    #[test_case("éaéa\n", "line 1: invalid rucksack \"éaéa\": expected only the letters a-z and A-Z, found 'é'"; "non ascii")]
+    #[test_case("abc\n", "line 1: invalid rucksack \"abc\": rucksack string description must have an even number of characters"; "odd length")]
+    fn test_bad_rucksacks(input: &str, expected: &str) {
+        let err = Day3Solver {}.parse(input).unwrap_err();
+        assert_eq!(format!("{:#}", err), expected);
     }
 }
@@ -1,58 +1,70 @@
-use crate::solver::Solver;
 use std::ops::RangeInclusive;
 
-use itertools::Itertools;
+use color_eyre::eyre::Result;
+use nom::character::complete::char;
+use nom::combinator::map;
+use nom::sequence::separated_pair;
+
+use crate::answer::Answer;
+use crate::generate::Generator;
+use crate::solver::Solver;
+use crate::utils::parse::{parse_lines, unsigned, PResult};
+use crate::utils::rng::Rng;
 
+#[derive(Default)]
 pub struct Day4Solver {}
 
 impl Solver for Day4Solver {
-    fn solve_part_1(&self, lines: Vec<String>) -> String {
-        get_ranges(&lines)
+    type Input = Vec<(RangeInclusive<u32>, RangeInclusive<u32>)>;
+
+    // The following parses the input and returns a Vec of InclusiveRange<u32> pairs. Each
+    // line is of the form "min-max,min-max", where min and max are u32.
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let pairs = parse_lines(input, parse_range_pair)?;
+        log::debug!("{} pairs", pairs.len());
+        Ok(pairs)
+    }
+
+    fn part1(&self, ranges: &Self::Input) -> Result<Answer> {
+        Ok(ranges
             .iter()
             .filter(|(a, b)| contains_latter(a, b) || contains_latter(b, a))
             .count()
-            .to_string()
+            .into())
     }
 
-    fn solve_part_2(&self, lines: Vec<String>) -> String {
-        get_ranges(&lines)
+    fn part2(&self, ranges: &Self::Input) -> Result<Answer> {
+        Ok(ranges
             .iter()
             .filter(|(a, b)| overlaps_latter(a, b) || overlaps_latter(b, a))
             .count()
-            .to_string()
+            .into())
     }
 }
 
-// The following functions parses a Vec of String and returns a Vec of InclusiveRange<u32>. Each
-// line is of the form "min-max", where min and max are u32.
-fn get_ranges(lines: &[String]) -> Vec<(RangeInclusive<u32>, RangeInclusive<u32>)> {
-    lines
-        // For each line...
-        .iter()
-        // Run a mapping function...
-        .map(|line| {
-            // That splits the line into two strings...
-            line.split(',')
-                // For each string, split into two using the hyphen, parse each element into a u32,
-                // and collect into a tuple of (u32, u32). Then convert each tuple into a range
-                .map(|range| {
-                    range
-                        .split('-')
-                        .map(|n| n.parse().expect("expected a u32"))
-                        .collect_tuple::<(u32, u32)>()
-                        .map(|(start, end)| start..=end)
-                        .expect("expected each range to be of the form number-number")
-                })
-                // At this point we have a bunch of RangeInclusives. We collect them into pairs
-                .collect_tuple::<(RangeInclusive<_>, RangeInclusive<_>)>()
-                // This makes sure that all our Option<X> are actually Some(x). That way we don't
-                // need to filter out the failures. We _probably_ should, but this is meant to be a
-                // quick exercise.
-                .expect("this should succeed")
-        })
-        // Collect the results into a Vec. Here the type can be inferred as RangeInclusive<_>,
-        // and that in turn was inferred to be RangeInclusive<u32>.
-        .collect::<Vec<_>>()
+impl Generator for Day4Solver {
+    /// The size is the number of pairs of elves.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut section = || {
+            let start = rng.range(1..100);
+            (start, rng.range(start..100))
+        };
+        (0..size.max(1))
+            .map(|_| {
+                let ((a, b), (c, d)) = (section(), section());
+                format!("{}-{},{}-{}\n", a, b, c, d)
+            })
+            .collect()
+    }
+}
+
+fn parse_range(i: &str) -> PResult<'_, RangeInclusive<u32>> {
+    // Two numbers separated by a hyphen, turned into a range.
+    map(separated_pair(unsigned, char('-'), unsigned), |(start, end)| start..=end)(i)
+}
+
+fn parse_range_pair(i: &str) -> PResult<'_, (RangeInclusive<u32>, RangeInclusive<u32>)> {
+    separated_pair(parse_range, char(','), parse_range)(i)
 }
 
 fn contains_latter<T: PartialOrd>(range1: &RangeInclusive<T>, range2: &RangeInclusive<T>) -> bool {
@@ -67,33 +79,19 @@ fn overlaps_latter<T: PartialOrd>(range1: &RangeInclusive<T>, range2: &RangeIncl
 mod test {
     use super::*;
 
-    use crate::lines_from_file;
-
-    #[test]
-    fn test_part_1() {
-        let solver = Day4Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day04.txt");
-        assert_eq!(solver.solve_part_1(lines), "2");
-    }
+    use crate::inputs::read_input;
 
     #[test]
     fn test_part_1_full() {
         let solver = Day4Solver {};
-        let lines = lines_from_file("./inputs/day04.txt");
-        assert_eq!(solver.solve_part_1(lines), "540");
-    }
-
-    #[test]
-    fn test_part_2() {
-        let solver = Day4Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day04.txt");
-        assert_eq!(solver.solve_part_2(lines), "4");
+        let input = solver.parse(&read_input("day04.txt").unwrap()).unwrap();
+        assert_eq!(solver.part1(&input).unwrap(), 540.into());
     }
 
     #[test]
     fn test_part_2_full() {
         let solver = Day4Solver {};
-        let lines = lines_from_file("./inputs/day04.txt");
-        assert_eq!(solver.solve_part_2(lines), "872");
+        let input = solver.parse(&read_input("day04.txt").unwrap()).unwrap();
+        assert_eq!(solver.part2(&input).unwrap(), 872.into());
     }
 }
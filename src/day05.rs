@@ -1,92 +1,162 @@
 use nom::{
     branch::alt,
     bytes::complete::{tag, take, take_while1},
-    combinator::{all_consuming, map, map_res, opt},
-    sequence::{delimited, preceded, tuple},
-    IResult,
+    character::complete::line_ending,
+    combinator::{map, map_opt},
+    error::context,
+    multi::many1,
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
 };
+use color_eyre::eyre::{eyre, Result, WrapErr};
 
-use crate::solver::Solver;
+use crate::answer::Answer;
+use crate::generate::Generator;
+use crate::solver::{Part, Solver};
+use crate::utils::parse::{columns, lines, parse_all, unsigned, PResult};
+use crate::utils::rng::Rng;
+use crate::visualize::{Frame, Visualize};
 
+#[derive(Default)]
 pub struct Day5Solver {}
 
 impl Day5Solver {
-    fn move_stacks_with_mode(&self, lines: Vec<String>, stack_mode: StackOrder) -> String {
-        let mut lines = lines.into_iter();
-        // This will advance the iterator until we are not able to parse.
-        let mut crate_lines: Vec<_> = (&mut lines)
-            .map_while(|line| {
-                all_consuming(parse_crate_line)(&line)
-                    .ok()
-                    // Recall that the output of parse_crate_line is a IResult<&str, Crate>. The
-                    // call to ok() returns an Option<(&str, rate)>. Then we use map to grab just
-                    // the crate.
-                    .map(|(_, cl)| cl)
-            })
+    fn move_stacks_with_mode(&self, procedure: &Procedure, stack_mode: StackOrder) -> Result<Answer> {
+        let crate_stacks = run_crane(procedure, stack_mode, |_, _, _| Ok(()))?;
+
+        let top_row: String = crate_stacks
+            .get_top_crates_for_stack()
+            .iter()
+            .map(|my_crate| my_crate.unwrap_or(Crate(' ')).0)
             .collect();
+
+        Ok(top_row.into())
+    }
+}
+
+impl Solver for Day5Solver {
+    type Input = Procedure;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        // The crate drawing and the instructions are separated by a blank line.
+        let (mut crate_lines, instructions) = parse_all(
+            input,
+            separated_pair(parse_drawing, pair(line_ending, line_ending), lines(parse_instruction)),
+        )?;
         // The stacks were given from the top down. Reverse the lines so that the crates can be
         // built from the bottom up.
         crate_lines.reverse();
-        let mut crate_stacks = CrateStacks::get_new_stacks(&crate_lines);
-
-        assert!(lines.next().unwrap().is_empty());
-
-        let instructions: Vec<_> = (&mut lines)
-            .map_while({
-                |line| {
-                    all_consuming(parse_instruction)(&line)
-                        .ok()
-                        .map(|(_, instr)| instr)
-                }
-            })
-            .collect();
+        let stacks = CrateStacks::get_new_stacks(&crate_lines)?;
+        log::debug!("{} crate lines and {} instructions", crate_lines.len(), instructions.len());
+        Ok(Procedure { stacks, instructions })
+    }
 
-        for ins in instructions {
-            crate_stacks.apply_instruction(&ins, stack_mode);
-        }
+    fn part1(&self, procedure: &Self::Input) -> Result<Answer> {
+        self.move_stacks_with_mode(procedure, StackOrder::Lifo)
+    }
 
-        let top_row = crate_stacks
-            .get_top_crates_for_stack()
-            .iter()
-            .map(|my_crate| my_crate.unwrap_or_else(|| Crate(' ')).0.to_string())
-            .reduce(|acc, e| acc + &e).unwrap();
+    fn part2(&self, procedure: &Self::Input) -> Result<Answer> {
+        self.move_stacks_with_mode(procedure, StackOrder::Fifo)
+    }
+}
 
-        top_row
+impl Visualize for Day5Solver {
+    /// Each frame is the drawing of the stacks after a move of the crane.
+    fn visualize(&self, part: Part, procedure: &Self::Input, frame: &mut dyn FnMut(Frame) -> Result<()>) -> Result<()> {
+        let stack_mode = match part {
+            Part::One => StackOrder::Lifo,
+            Part::Two => StackOrder::Fifo,
+        };
+        let moves = procedure.instructions.len();
+        frame(Frame::new("the starting arrangement", procedure.stacks.render()))?;
+        run_crane(procedure, stack_mode, |idx, ins, stacks| {
+            let title = format!(
+                "move {} from {} to {} ({} of {})",
+                ins.quantity,
+                ins.src + 1,
+                ins.dest + 1,
+                idx + 1,
+                moves
+            );
+            frame(Frame::new(title, stacks.render()))
+        })?;
+        Ok(())
     }
 }
 
-impl Solver for Day5Solver {
-    fn solve_part_1(&self, lines: Vec<String>) -> String {
-        self.move_stacks_with_mode(lines, StackOrder::Lifo)
+impl Generator for Day5Solver {
+    /// The size is the number of moves the crane makes. The moves are played out as they are
+    /// generated, so that none of them takes more crates than its stack holds.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut stacks: Vec<usize> = (0..rng.range(2..10)).map(|_| rng.range(1..9)).collect();
+        let height = stacks.iter().copied().max().unwrap_or(0);
+        let mut output = String::new();
+        for level in (0..height).rev() {
+            let row: Vec<String> = stacks
+                .iter()
+                .map(|&crates| match crates > level {
+                    true => format!("[{}]", (b'A' + rng.below(26) as u8) as char),
+                    false => "   ".to_string(),
+                })
+                .collect();
+            output += &(row.join(" ") + "\n");
+        }
+        let numbers: Vec<String> = (1..=stacks.len()).map(|n| format!(" {} ", n)).collect();
+        output += &(numbers.join(" ") + "\n\n");
+
+        for _ in 0..size.max(1) {
+            let nonempty: Vec<usize> = (0..stacks.len()).filter(|&s| stacks[s] > 0).collect();
+            let src = *rng.pick(&nonempty);
+            let dest = (src + rng.range(1..stacks.len())) % stacks.len();
+            let quantity = rng.range(1..stacks[src] + 1);
+            stacks[src] -= quantity;
+            stacks[dest] += quantity;
+            output += &format!("move {} from {} to {}\n", quantity, src + 1, dest + 1);
+        }
+        output
     }
+}
 
-    fn solve_part_2(&self, lines: Vec<String>) -> String {
-        self.move_stacks_with_mode(lines, StackOrder::Fifo)
+/// run_crane applies every instruction in order and returns the stacks at the end. `after_move`
+/// is called with the stacks after each instruction.
+fn run_crane(
+    procedure: &Procedure,
+    stack_mode: StackOrder,
+    mut after_move: impl FnMut(usize, &Instruction, &CrateStacks) -> Result<()>,
+) -> Result<CrateStacks> {
+    let mut crate_stacks = procedure.stacks.clone();
+    for (idx, ins) in procedure.instructions.iter().enumerate() {
+        crate_stacks
+            .apply_instruction(ins, stack_mode)
+            .wrap_err_with(|| format!("could not apply instruction {}: {:?}", idx + 1, ins))?;
+        log::trace!("move {} from {} to {}: {:?}", ins.quantity, ins.src + 1, ins.dest + 1, crate_stacks);
+        after_move(idx, ins, &crate_stacks)?;
     }
+    Ok(crate_stacks)
 }
 
-#[derive(Debug)]
+/// Procedure is the starting arrangement of the crates, and the moves the crane will make.
+#[derive(Debug, Clone)]
+pub struct Procedure {
+    stacks: CrateStacks,
+    instructions: Vec<Instruction>,
+}
+
+#[derive(Debug, Clone)]
 struct Instruction {
     quantity: usize,
     src: usize,
     dest: usize,
 }
 
-// Taken from fasterthanlime's advent of code.
-fn parse_number(i: &str) -> IResult<&str, usize> {
-    map_res(take_while1(|c: char| c.is_ascii_digit()), |s: &str| {
-        s.parse::<usize>()
-    })(i)
-}
-
-fn parse_pile_number(i: &str) -> IResult<&str, usize> {
-    map(parse_number, |i| i - 1)(i)
+fn parse_pile_number(i: &str) -> PResult<'_, usize> {
+    // Piles are numbered from 1, so a pile number of 0 is a parse failure.
+    context("a pile number", map_opt(unsigned, |i: usize| i.checked_sub(1)))(i)
 }
 
-fn parse_instruction(s: &str) -> IResult<&str, Instruction> {
+fn parse_instruction(s: &str) -> PResult<'_, Instruction> {
     map(
         tuple((
-            preceded(tag("move "), parse_number),
+            preceded(tag("move "), unsigned),
             preceded(tag(" from "), parse_pile_number),
             preceded(tag(" to "), parse_pile_number),
         )),
@@ -134,30 +204,61 @@ enum StackOrder {
 }
 
 #[derive(Debug, Clone)]
-struct CrateStacks(Vec<CrateStack>);
+pub struct CrateStacks(Vec<CrateStack>);
 
 impl CrateStacks {
-    fn get_new_stacks(lines: &Vec<Vec<Option<Crate>>>) -> Self {
-        let mut stacks = vec![CrateStack(vec![]); lines.first().unwrap().len()];
+    fn get_new_stacks(lines: &[Vec<Option<Crate>>]) -> Result<Self> {
+        let bottom = lines
+            .first()
+            .ok_or_else(|| eyre!("expected at least one row of crates"))?;
+        let mut stacks = vec![CrateStack(vec![]); bottom.len()];
         for row in lines {
             for (i, col) in row.iter().enumerate() {
-                match col {
-                    Some(c) => stacks[i].0.push(*c),
-                    None => {}
+                if let Some(c) = col {
+                    stacks
+                        .get_mut(i)
+                        .ok_or_else(|| eyre!("crate {:?} is not above any stack", c.0))?
+                        .0
+                        .push(*c);
                 }
             }
         }
-        Self(stacks)
+        Ok(Self(stacks))
     }
 
-    fn apply_instruction(&mut self, instr: &Instruction, stack_order: StackOrder) {
+    fn apply_instruction(&mut self, instr: &Instruction, stack_order: StackOrder) -> Result<()> {
+        let num_stacks = self.0.len();
         let crates = self
             .0
             .get_mut(instr.src)
-            .unwrap()
+            .ok_or_else(|| eyre!("there is no stack {} (there are {})", instr.src + 1, num_stacks))?
             .remove_crates(instr.quantity, stack_order)
-            .unwrap();
-        self.0.get_mut(instr.dest).unwrap().add_crates(&crates);
+            .ok_or_else(|| eyre!("stack {} has fewer than {} crates", instr.src + 1, instr.quantity))?;
+        self.0
+            .get_mut(instr.dest)
+            .ok_or_else(|| eyre!("there is no stack {} (there are {})", instr.dest + 1, num_stacks))?
+            .add_crates(&crates);
+        Ok(())
+    }
+
+    /// render draws the stacks the way the puzzle input does, with the stack numbers underneath.
+    fn render(&self) -> String {
+        let height = self.0.iter().map(|stack| stack.0.len()).max().unwrap_or(0);
+        let mut drawing = String::new();
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .0
+                .iter()
+                .map(|stack| match stack.0.get(level) {
+                    Some(c) => format!("[{}]", c.0),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            drawing += row.join(" ").trim_end();
+            drawing.push('\n');
+        }
+        let numbers: Vec<String> = (1..=self.0.len()).map(|n| format!(" {} ", n)).collect();
+        drawing + numbers.join(" ").trim_end() + "\n"
     }
 
     fn get_top_crates_for_stack(&self) -> Vec<Option<Crate>> {
@@ -175,7 +276,7 @@ struct Crate(char);
 
 // This function consumes a &str, and emits the remaining str and the crate that
 // lives there. This ignores errors.
-fn parse_crate(s: &str) -> IResult<&str, Crate> {
+fn parse_crate(s: &str) -> PResult<'_, Crate> {
     // This is obvious: Consume a [, grab and return the next char, and consume ]. In
     // this context, consume means to discard.
     let parser = delimited(tag("["), take(1_usize), tag("]"));
@@ -202,77 +303,59 @@ fn parse_crate(s: &str) -> IResult<&str, Crate> {
     map(parser, |ss: &str| Crate(ss.chars().next().unwrap()))(s)
 }
 
-fn parse_hole(s: &str) -> IResult<&str, ()> {
+fn parse_hole(s: &str) -> PResult<'_, ()> {
     // drop is a built-in function that takes a value and returns nothing.
     map(tag("   "), drop)(s)
 }
 
-fn parse_crate_or_hole(s: &str) -> IResult<&str, Option<Crate>> {
+fn parse_crate_or_hole(s: &str) -> PResult<'_, Option<Crate>> {
     // alt is a function that takes a list of parsers and tries them in order.
     // If any of them succeed, it returns the result of that parser.
     // If none of them succeed, it returns an error.
     alt((map(parse_crate, Some), map(parse_hole, |_| None)))(s)
 }
 
-fn parse_crate_line(s: &str) -> IResult<&str, Vec<Option<Crate>>> {
-    // Here we consume a line and produce a vector where
-    // each element is a column.
-
-    // Here we say that i is mutable but c is not.
-    let (mut i, c) = parse_crate_or_hole(s)?;
-    let mut v = vec![c];
-
-    loop {
-        // The preceded parser consumes and ignores the first element (a space), then
-        // returns the value from the second parser. You can read this as:
-        // "Consuem a space, then a crate or a hole".
-        // This whole operation can fail, so the opt function will return a None instead
-        // of an error.
-        let (next_i, maybe_a_crate) = opt(preceded(tag(" "), parse_crate_or_hole))(i)?;
-        match maybe_a_crate {
-            // Found another crate, keep it going!
-            Some(a_crate) => v.push(a_crate),
-            // We are done!
-            None => break,
-        }
-        i = next_i;
-    }
+fn parse_crate_line(s: &str) -> PResult<'_, Vec<Option<Crate>>> {
+    // Here we consume a line and produce a vector where each element is a column. Every
+    // column is three characters wide, and the columns are separated by a space.
+    columns(3, " ", parse_crate_or_hole)(s)
+}
+
+fn parse_stack_numbers(s: &str) -> PResult<'_, &str> {
+    // The line under the drawing numbers the stacks, which we don't need.
+    context("the stack numbers", take_while1(|c: char| c == ' ' || c.is_ascii_digit()))(s)
+}
 
-    // We have finished and can return the remaining string and vector
-    Ok((i, v))
+fn parse_drawing(s: &str) -> PResult<'_, Vec<Vec<Option<Crate>>>> {
+    // Keep reading rows of crates until we reach the line that numbers the stacks.
+    terminated(many1(terminated(parse_crate_line, line_ending)), parse_stack_numbers)(s)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    use crate::lines_from_file;
+    use crate::inputs::read_input;
 
     #[test]
-    fn test_part_1() {
-        let solver = Day5Solver{};
-        let lines = lines_from_file("./inputs/unit_test/day05.txt");
-        assert_eq!(solver.solve_part_1(lines), "CMZ");
-    }
-
-    #[test]
-    fn test_part_2() {
+    fn test_part_1_full() {
         let solver = Day5Solver{};
-        let lines = lines_from_file("./inputs/unit_test/day05.txt");
-        assert_eq!(solver.solve_part_2(lines), "MCD");
+        let input = solver.parse(&read_input("day05.txt").unwrap()).unwrap();
+        assert_eq!(solver.part1(&input).unwrap(), "TLNGFGMFN".into());
     }
 
     #[test]
-    fn test_part_1_full() {
+    fn test_part_2_full() {
         let solver = Day5Solver{};
-        let lines = lines_from_file("./inputs/day05.txt");
-        assert_eq!(solver.solve_part_1(lines), "TLNGFGMFN");
+        let input = solver.parse(&read_input("day05.txt").unwrap()).unwrap();
+        assert_eq!(solver.part2(&input).unwrap(), "FGLQJCMBD".into());
     }
 
     #[test]
-    fn test_part_2_full() {
+    fn test_bad_instruction_reports_its_position() {
         let solver = Day5Solver{};
-        let lines = lines_from_file("./inputs/day05.txt");
-        assert_eq!(solver.solve_part_2(lines), "FGLQJCMBD");
+        let input = "    [D]\n[N] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 1 from 0 to 2\n";
+        let err = solver.parse(input).unwrap_err().to_string();
+        assert!(err.starts_with("line 6, column 13: invalid pile number"), "{err}");
     }
 }
@@ -2,18 +2,25 @@
 // these problems as if this was a work assignment, pulling in
 // crates if necessary and implementing the functionality if it's
 // fun or worthwhile.
+use std::collections::HashSet;
 use std::fmt::{Debug, Formatter};
 
 use indextree::{Arena, NodeId};
-use nom::{Finish, IResult};
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take_while1};
-use nom::combinator::{all_consuming, map};
+use nom::combinator::map;
+use nom::error::context;
 use nom::sequence::{preceded, separated_pair};
+use color_eyre::eyre::{eyre, Result};
 
+use crate::answer::Answer;
+use crate::generate::Generator;
+use crate::repl::{arg, Command as ReplCommand, Explore};
 use crate::solver::Solver;
+use crate::utils::parse::{parse_lines, unsigned, PResult};
+use crate::utils::rng::Rng;
 
-fn parse_path(i: &str) -> IResult<&str, String> {
+fn parse_path(i: &str) -> PResult<'_, String> {
     // This says "grab one character at a time until it doesn't match the given condition, then
     // convert into SOMETHING". That SOMETHING is inferred from the function signature, and that
     // is a String.
@@ -26,7 +33,7 @@ fn parse_path(i: &str) -> IResult<&str, String> {
 #[derive(Debug)]
 struct Ls;
 
-fn parse_ls(i: &str) -> IResult<&str, Ls> {
+fn parse_ls(i: &str) -> PResult<'_, Ls> {
     // Tag consumes a "ls" string and returns what's left. This errors if there is no "ls"
     map(
         tag("ls"),
@@ -37,12 +44,15 @@ fn parse_ls(i: &str) -> IResult<&str, Ls> {
 const LARGE_DIR_THRESHOLD_SIZE: usize = 100_000;
 const MINIMUM_INSTALL_SIZE: usize = 30_000_000;
 const TOTAL_DISK_SIZE: usize = 70_000_000;
+/// How deep generated directory trees go. The sizes of directories are added up recursively, so
+/// the depth has to stay well within the stack.
+const GENERATED_MAX_DEPTH: usize = 100;
 
 
 #[derive(Debug)]
 struct Cd(String);
 
-fn parse_cd(i: &str) -> IResult<&str, Cd> {
+fn parse_cd(i: &str) -> PResult<'_, Cd> {
     // This says "make sure that the input is preceded by a cd, then parse using parse_path"
     map(
         preceded(tag("cd "), parse_path),
@@ -68,7 +78,7 @@ impl From<Cd> for Command {
     }
 }
 
-fn parse_command_line(i: &str) -> IResult<&str, Command> {
+fn parse_command_line(i: &str) -> PResult<'_, Command> {
     // If these functions succeed they return the remaining input and the parsed output.
     // In this case we don't care about the parsed_output.
     let (i, _) = tag("$ ")(i)?;
@@ -89,11 +99,11 @@ enum LsEntry {
 }
 
 /// parse_ls_entry parses the output of running ls
-fn parse_ls_lines(i: &str) -> IResult<&str, LsEntry> {
+fn parse_ls_lines(i: &str) -> PResult<'_, LsEntry> {
     let parse_file_line = map(
         // Use two parsing functions separated by some other combinator, in this case an empty space
         separated_pair(
-            nom::character::complete::u64,
+            unsigned,
             tag(" "),
             parse_path,
         ),
@@ -116,15 +126,19 @@ enum Line {
     LsEntry(LsEntry),
 }
 
-fn parse_lines(i: &str) -> IResult<&str, Line> {
-    alt(
-        (
-            map(parse_command_line, Line::Command),
-            map(parse_ls_lines, Line::LsEntry),
-        )
+fn parse_line(i: &str) -> PResult<'_, Line> {
+    context(
+        "a command or a directory entry",
+        alt(
+            (
+                map(parse_command_line, Line::Command),
+                map(parse_ls_lines, Line::LsEntry),
+            )
+        ),
     )(i)
 }
 
+#[derive(Clone)]
 struct FsEntry {
     name: String,
     size: Option<usize>,
@@ -146,16 +160,51 @@ impl Debug for FsEntry {
     }
 }
 
+#[derive(Default)]
 pub struct Day7Solver {}
 
-fn tree_from_cmd_lines(lines: Vec<Line>) -> (Arena<FsEntry>, NodeId) {
+fn parse_cmd_lines(input: &str) -> Result<Vec<Line>> {
+    Ok(parse_lines(input, parse_line)?)
+}
+
+/// FileSystem is the directory tree rebuilt from the terminal output.
+#[derive(Clone)]
+pub struct FileSystem {
+    tree: Arena<FsEntry>,
+    root: NodeId,
+}
+
+impl FileSystem {
+    /// find returns the entry at a path like `/a/e`. Paths start from the root whether or not
+    /// they start with a slash.
+    fn find(&self, path: &str) -> Result<NodeId> {
+        path.split('/').filter(|name| !name.is_empty()).try_fold(self.root, |dir, name| {
+            dir.children(&self.tree)
+                .find(|&child| self.tree[child].get().name == name)
+                .ok_or_else(|| eyre!("there is no {:?} in {}", name, self.path(dir)))
+        })
+    }
+
+    /// path returns the full path of an entry.
+    fn path(&self, node: NodeId) -> String {
+        let mut names: Vec<&str> = node
+            .ancestors(&self.tree)
+            .filter(|&n| n != self.root)
+            .map(|n| self.tree[n].get().name.as_str())
+            .collect();
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+}
+
+fn tree_from_cmd_lines(lines: Vec<Line>) -> Result<FileSystem> {
     let mut tree: Arena<FsEntry> = Arena::new();
     let mut id = tree.new_node(FsEntry {
         name: "/".to_string(),
         size: None,
     });
     let root_id = id;
-    for line in lines {
+    for (idx, line) in lines.into_iter().enumerate() {
         match line {
             Line::Command(cmd) => {
                 match cmd {
@@ -165,15 +214,17 @@ fn tree_from_cmd_lines(lines: Vec<Line>) -> (Arena<FsEntry>, NodeId) {
                     Command::Cd(location) => match location.as_str() {
                         "/" => {}
                         ".." => {
-                            id = tree[id].parent().unwrap();
+                            id = tree[id]
+                                .parent()
+                                .ok_or_else(|| eyre!("line {}: cannot cd .. from the root directory", idx + 1))?;
                         }
                         _ => {
-                            for child in id.children(&tree) {
-                                if tree[child].get().name == location {
-                                    id = child;
-                                    break;
-                                }
-                            }
+                            id = id
+                                .children(&tree)
+                                .find(|&child| tree[child].get().name == location)
+                                .ok_or_else(|| {
+                                    eyre!("line {}: cannot cd into unknown directory {:?}", idx + 1, location)
+                                })?;
                         }
                     }
                 }
@@ -203,58 +254,155 @@ fn tree_from_cmd_lines(lines: Vec<Line>) -> (Arena<FsEntry>, NodeId) {
             }
         }
     }
-    println!("{:?}", root_id.debug_pretty_print(&tree));
-    (tree, root_id)
+    log::debug!("filesystem:\n{:?}", root_id.debug_pretty_print(&tree));
+    Ok(FileSystem { tree, root: root_id })
 }
 
 impl Solver for Day7Solver {
+    type Input = FileSystem;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        tree_from_cmd_lines(parse_cmd_lines(input)?)
+    }
+
     // This solution involves building the FS tree and navigating it and finding all directories
     // that are smaller than the given size.
-    fn solve_part_1(&self, lines: Vec<String>) -> String {
-        let lines = lines.into_iter()
-            .map(|l| all_consuming(parse_lines)(&l).finish().unwrap().1).collect();
-
-        let (tree, root_id) = tree_from_cmd_lines(lines);
+    fn part1(&self, fs: &Self::Input) -> Result<Answer> {
+        let (tree, root_id) = (&fs.tree, fs.root);
 
         let mut cum_sum_small_dirs_size = 0;
-        for node in root_id.descendants(&tree) {
+        for node in root_id.descendants(tree) {
             if !tree[node].get().is_dir() {
                 continue;
             }
-            let size = get_size_of_tree(node, &tree);
+            let size = get_size_of_tree(node, tree);
             if size <= LARGE_DIR_THRESHOLD_SIZE {
                 cum_sum_small_dirs_size += size;
             }
         }
-        format!("{}", cum_sum_small_dirs_size)
+        Ok(cum_sum_small_dirs_size.into())
     }
 
     // For this solution we iterate through all the directories and finding the smallest directory
     // that will delete the necessary amount.
-    fn solve_part_2(&self, lines: Vec<String>) -> String {
-        let lines = lines.into_iter()
-            .map(|l| all_consuming(parse_lines)(&l).finish().unwrap().1).collect();
-
-        let (tree, root_id) = tree_from_cmd_lines(lines);
-        let current_size = get_size_of_tree(root_id, &tree);
-        let mut smallest_dir_size = usize::MAX;
-        for node in root_id.descendants(&tree) {
+    fn part2(&self, fs: &Self::Input) -> Result<Answer> {
+        let (tree, root_id) = (&fs.tree, fs.root);
+        let current_size = get_size_of_tree(root_id, tree);
+        let free_space = TOTAL_DISK_SIZE
+            .checked_sub(current_size)
+            .ok_or_else(|| eyre!("the filesystem uses {} bytes, more than the disk holds", current_size))?;
+        let mut smallest_dir_size = None;
+        for node in root_id.descendants(tree) {
             if !tree[node].get().is_dir() {
                 continue;
             }
-            let size = get_size_of_tree(node, &tree);
-            if TOTAL_DISK_SIZE - (current_size - size) >= MINIMUM_INSTALL_SIZE && size < smallest_dir_size {
-                smallest_dir_size = size;
+            let size = get_size_of_tree(node, tree);
+            if free_space + size >= MINIMUM_INSTALL_SIZE && smallest_dir_size.is_none_or(|s| size < s) {
+                smallest_dir_size = Some(size);
+            }
+        }
+        let smallest_dir_size = smallest_dir_size
+            .ok_or_else(|| eyre!("no directory frees up enough space for the update"))?;
+        Ok(smallest_dir_size.into())
+    }
+}
+
+impl Explore for Day7Solver {
+    type State = FileSystem;
+
+    fn explore(&self, fs: &Self::Input) -> Result<Self::State> {
+        Ok(fs.clone())
+    }
+
+    fn commands(&self) -> Vec<ReplCommand<Self::State>> {
+        vec![
+            ReplCommand::new("size", "<dir>", "the total size of a directory or file", |fs, args| {
+                let node = fs.find(&arg::<String>(args, 0, "dir")?)?;
+                Ok(get_size_of_tree(node, &fs.tree).to_string())
+            }),
+            ReplCommand::new("ls", "[dir]", "list a directory, with the size of everything in it", |fs, args| {
+                let dir = fs.find(args.first().unwrap_or(&"/"))?;
+                if !fs.tree[dir].get().is_dir() {
+                    return Err(eyre!("{} is a file", fs.path(dir)));
+                }
+                let entries: Vec<String> = dir
+                    .children(&fs.tree)
+                    .map(|child| {
+                        let entry = fs.tree[child].get();
+                        let kind = if entry.is_dir() { "dir" } else { "file" };
+                        format!("{:<4} {:>10}  {}", kind, get_size_of_tree(child, &fs.tree), entry.name)
+                    })
+                    .collect();
+                Ok(entries.join("\n"))
+            }),
+        ]
+    }
+}
+
+impl Generator for Day7Solver {
+    /// The size is the number of files and directories. Half of the entries go in the directory
+    /// made last, which makes for deep trees, up to `GENERATED_MAX_DEPTH` levels. The files add
+    /// up to between 40M and 60M, so that there is always a directory big enough to delete.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        struct Dir {
+            depth: usize,
+            names: HashSet<String>,
+            files: Vec<String>,
+            dirs: Vec<(String, usize)>,
+        }
+        let new_dir = |depth| Dir { depth, names: HashSet::new(), files: vec![], dirs: vec![] };
+        let mut dirs = vec![new_dir(0)];
+        let mut weights = vec![];
+        for _ in 0..size.max(1) {
+            let mut parent = if rng.chance(0.5) { dirs.len() - 1 } else { rng.below(dirs.len()) };
+            if dirs[parent].depth >= GENERATED_MAX_DEPTH {
+                parent = 0;
+            }
+            let name = loop {
+                let name: String = (0..rng.range(1..9)).map(|_| (b'a' + rng.below(26) as u8) as char).collect();
+                let name = if rng.chance(0.5) { name + "." + *rng.pick(&["txt", "dat", "log", "c"]) } else { name };
+                if dirs[parent].names.insert(name.clone()) {
+                    break name;
+                }
+            };
+            if rng.chance(0.3) {
+                let (child, depth) = (dirs.len(), dirs[parent].depth + 1);
+                dirs[parent].dirs.push((name, child));
+                dirs.push(new_dir(depth));
+            } else {
+                dirs[parent].files.push(name);
+                weights.push(rng.range(1..1000));
             }
         }
-        format!("{}", smallest_dir_size)
+
+        let budget = rng.range(40_000_000..60_000_000).saturating_sub(weights.len());
+        let total: usize = weights.iter().sum();
+        let mut sizes = weights.into_iter().map(|w| 1 + w * budget / total.max(1));
+
+        fn list(dirs: &[Dir], dir: usize, sizes: &mut impl Iterator<Item = usize>, output: &mut String) {
+            *output += "$ ls\n";
+            for (name, _) in &dirs[dir].dirs {
+                *output += &format!("dir {}\n", name);
+            }
+            for name in &dirs[dir].files {
+                *output += &format!("{} {}\n", sizes.next().expect("every file has a size"), name);
+            }
+            for (name, child) in &dirs[dir].dirs {
+                *output += &format!("$ cd {}\n", name);
+                list(dirs, *child, sizes, output);
+                *output += "$ cd ..\n";
+            }
+        }
+        let mut output = "$ cd /\n".to_string();
+        list(&dirs, 0, &mut sizes, &mut output);
+        output
     }
 }
 
 fn get_size_of_tree(node_id: NodeId, arena: &Arena<FsEntry>) -> usize {
     let entry = arena[node_id].get();
-    if !entry.is_dir() {
-        return entry.size.unwrap();
+    if let Some(size) = entry.size {
+        return size;
     }
     let mut tree_size = 0;
     for child in node_id.children(arena) {
@@ -265,35 +413,52 @@ fn get_size_of_tree(node_id: NodeId, arena: &Arena<FsEntry>) -> usize {
 
 #[cfg(test)]
 mod test {
+    use test_case::test_case;
+
     use crate::day07::Day7Solver;
-    use crate::lines_from_file;
+    use crate::inputs::{self, read_input};
+    use crate::repl::{Repl, Reply};
     use crate::solver::Solver;
 
     #[test]
-    fn test_part_1() {
+    fn test_part_1_full() {
         let solver = Day7Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day07.txt");
-        assert_eq!(solver.solve_part_1(lines), "95437")
+        let input = solver.parse(&read_input("day07.txt").unwrap()).unwrap();
+        assert_eq!(solver.part1(&input).unwrap(), 1491614.into())
     }
 
     #[test]
-    fn test_part_1_full() {
+    fn test_part_2_full() {
         let solver = Day7Solver {};
-        let lines = lines_from_file("./inputs/day07.txt");
-        assert_eq!(solver.solve_part_1(lines), "1491614")
+        let input = solver.parse(&read_input("day07.txt").unwrap()).unwrap();
+        assert_eq!(solver.part2(&input).unwrap(), 6400111.into())
     }
 
     #[test]
-    fn test_part_2() {
+    fn test_paths_are_lowercase_letters_dots_and_slashes() {
         let solver = Day7Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day07.txt");
-        assert_eq!(solver.solve_part_2(lines), "24933642")
+        assert!(solver.parse("$ cd /\n$ ls\ndir a.b\n12 c.txt\n$ cd a.b\n").is_ok());
+        let err = solver.parse("$ cd /\n$ ls\n12 File-1.txt\n").err().unwrap();
+        assert_eq!(
+            format!("{:#}", err),
+            "line 3, column 1: expected a command or a directory entry\n  |\n3 | 12 File-1.txt\n  | ^"
+        );
+    }
+
+    #[test_case("size /a/e", "584")]
+    #[test_case("size d", "24933642"; "relative to the root")]
+    #[test_case("size /", "48381165"; "root")]
+    #[test_case("ls /a", "dir         584  e\nfile      29116  f\nfile       2557  g\nfile      62596  h.lst")]
+    fn test_repl(command: &str, expected: &str) {
+        let mut repl = Repl::new(7, &read_input(inputs::example_input(7)).unwrap(), None).unwrap();
+        assert_eq!(repl.execute(command).unwrap(), Reply::Print(expected.to_string()));
     }
 
     #[test]
-    fn test_part_2_full() {
-        let solver = Day7Solver {};
-        let lines = lines_from_file("./inputs/day07.txt");
-        assert_eq!(solver.solve_part_2(lines), "6400111")
+    fn test_repl_errors() {
+        let mut repl = Repl::new(7, &read_input(inputs::example_input(7)).unwrap(), None).unwrap();
+        assert_eq!(repl.execute("size /a/x").unwrap_err().to_string(), "there is no \"x\" in /a");
+        assert_eq!(repl.execute("ls /b.txt").unwrap_err().to_string(), "/b.txt is a file");
+        assert!(repl.execute("size").is_err());
     }
 }
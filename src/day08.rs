@@ -1,9 +1,19 @@
-use crate::solver::Solver;
-use crate::utils::grid::{Grid, GridCoord};
+use color_eyre::eyre::{eyre, Result};
+use itertools::Either;
 
+use crate::answer::Answer;
+use crate::generate::Generator;
+use crate::repl::{arg, Command, Explore};
+use crate::solver::{Part, Solver};
+use crate::utils::grid::{CellChar, Direction, Grid, GridCoord};
+use crate::utils::rng::Rng;
+use crate::visualize::{Frame, Visualize};
+
+#[derive(Default)]
 pub struct Day8Solver {}
 
-struct Forest {
+#[derive(Clone)]
+pub struct Forest {
     num_visible: usize,
     trees: Grid<u8>,
     tree_is_visible: Grid<bool>,
@@ -19,126 +29,127 @@ struct MaxTreeView {
 
 }
 
+impl MaxTreeView {
+    fn scenic_score(&self) -> usize {
+        self.top_to_bottom.distance
+            * self.bottom_to_top.distance
+            * self.left_to_right.distance
+            * self.right_to_left.distance
+    }
+}
+
 #[derive(Copy, Clone, Default)]
 struct MaxTreeViewInDirection {
     distance: usize,
 }
 
-enum ForestDirection {
-    TopToBottom,
-    BottomToTop,
-    LeftToRight,
-    RightToLeft,
-}
-
 impl Forest {
-    fn new(lines: Vec<String>) -> Self {
-        let grid_height = lines.len();
-        let grid_width = lines[0].len();
-        let mut trees = Grid::new(grid_width, grid_height);
-        for (row, line) in lines.iter().enumerate() {
-            for (col, height) in line.chars().enumerate() {
-                let tree_height: &mut u8 = trees.cell_mut((row as isize, col as isize).into()).unwrap();
-                *tree_height = height.to_digit(10).unwrap() as u8;
-            }
+    fn new(input: &str) -> Result<Self> {
+        let trees: Grid<u8> = input.parse()?;
+        if trees.height() == 0 {
+            return Err(eyre!("expected at least one row of trees"));
         }
-        Self {
+        log::debug!("a forest {} trees wide and {} trees high", trees.width(), trees.height());
+        Ok(Self {
             num_visible: 0,
+            tree_is_visible: Grid::new(trees.width(), trees.height()),
+            max_tree_visibility: Grid::new(trees.width(), trees.height()),
             trees,
-            tree_is_visible: Grid::new(grid_height, grid_height),
-            max_tree_visibility: Grid::new(grid_height, grid_height),
-        }
+        })
     }
 
-    /* Function used for debugging
-    fn print_visible(&self) {
-        for row in 0..self.trees.height() {
-            let mut str: String = "".to_string();
-            for col in 0..self.trees.width() {
-                if *self.tree_is_visible.cell((row as isize, col as isize).into()).unwrap() {
-                    str += "X"
-                } else {
-                    str += "0"
-                }
-            }
-            println!("{}", str)
+    /// render_visible draws the forest with an X for every tree known to be visible from
+    /// outside it, and a 0 for the others.
+    fn render_visible(&self) -> String {
+        self.tree_is_visible.render_with(|_, &visible| if visible { 'X' } else { '0' })
+    }
+
+    /// render_best draws the heights of the trees, with a * for the given one.
+    fn render_best(&self, best: GridCoord) -> String {
+        self.trees.render_with(|coord, height| if coord == best { '*' } else { height.to_char() })
+    }
+
+    /// tree_at returns the coordinate of the tree at a row and column counted from 1, as they
+    /// are given in the REPL.
+    fn tree_at(&self, args: &[&str]) -> Result<GridCoord> {
+        let (row, col): (usize, usize) = (arg(args, 0, "row")?, arg(args, 1, "column")?);
+        let coord: GridCoord = GridCoord::from_row_col(row as isize - 1, col as isize - 1);
+        if row == 0 || col == 0 || !self.trees.in_bounds(coord) {
+            return Err(eyre!(
+                "there is no tree at row {}, column {}, the forest is {} by {}",
+                row,
+                col,
+                self.trees.height(),
+                self.trees.width()
+            ));
         }
+        Ok(coord)
     }
-     */
 
     fn update_max_tree_visibility(&mut self) {
-        for idx in self.trees.grid_coordinates() {
-            let max_tree_view = MaxTreeView {
-                left_to_right: self.calculate_tree_visibility(idx, ForestDirection::LeftToRight),
-                right_to_left: self.calculate_tree_visibility(idx, ForestDirection::RightToLeft),
-                top_to_bottom: self.calculate_tree_visibility(idx, ForestDirection::TopToBottom),
-                bottom_to_top: self.calculate_tree_visibility(idx, ForestDirection::BottomToTop),
-            };
+        for idx in self.trees.coordinates() {
+            let max_tree_view = self.max_tree_view(idx);
+            log::trace!("tree at row {}, column {}: scenic score {}", idx.row() + 1, idx.col() + 1, max_tree_view.scenic_score());
             let view = self.max_tree_visibility.cell_mut(idx).unwrap();
             *view = Some(max_tree_view);
         }
     }
 
-    fn calculate_tree_visibility(&self, coord: GridCoord, direction: ForestDirection) -> MaxTreeViewInDirection {
-        let movement: GridCoord = match direction {
-            ForestDirection::TopToBottom => { (0, 1).into() }
-            ForestDirection::BottomToTop => { (0, -1).into() }
-            ForestDirection::LeftToRight => { (1, 0).into() }
-            ForestDirection::RightToLeft => { (-1, 0).into() }
-        };
-        let mut new_coord = coord + movement;
+    fn max_tree_view(&self, idx: GridCoord) -> MaxTreeView {
+        MaxTreeView {
+            left_to_right: self.calculate_tree_visibility(idx, Direction::Right),
+            right_to_left: self.calculate_tree_visibility(idx, Direction::Left),
+            top_to_bottom: self.calculate_tree_visibility(idx, Direction::Down),
+            bottom_to_top: self.calculate_tree_visibility(idx, Direction::Up),
+        }
+    }
+
+    fn calculate_tree_visibility(&self, coord: GridCoord, direction: Direction) -> MaxTreeViewInDirection {
         let current_tree_height = *self.trees.cell(coord).unwrap();
         let mut dist = 0;
-        while self.trees.in_bounds(new_coord) {
+        for next_coord in self.trees.ray(coord, direction.offset()) {
             dist += 1;
-            let next_tree_height = *self.trees.cell(new_coord).unwrap();
-            if next_tree_height >= current_tree_height {
-                return MaxTreeViewInDirection { distance: dist };
+            if *self.trees.cell(next_coord).unwrap() >= current_tree_height {
+                break;
             }
-            new_coord = new_coord + movement;
         }
         MaxTreeViewInDirection { distance: dist }
     }
 
-    fn tree_coordinates(&self, direction: ForestDirection, col_or_row: usize) -> Vec<GridCoord> {
+    /// tree_coordinates returns the trees of a row or column, in the order they are seen when
+    /// looking in the given direction, which must be along it.
+    fn tree_coordinates(&self, direction: Direction, col_or_row: usize) -> impl Iterator<Item = GridCoord> {
         match direction {
-            ForestDirection::TopToBottom | ForestDirection::BottomToTop => {
-                let col = col_or_row;
-                let mut indexes = vec![];
-                for row in 0..self.trees.height() {
-                    indexes.push((row as isize, col as isize).into())
-                }
-                if let ForestDirection::BottomToTop = direction {
-                    indexes.reverse()
-                }
-                indexes
-            }
-            ForestDirection::LeftToRight | ForestDirection::RightToLeft => {
-                let row = col_or_row;
-                let mut indexes = vec![];
-                for col in 0..self.trees.width() {
-                    indexes.push((row as isize, col as isize).into())
-                }
-                if let ForestDirection::RightToLeft = direction {
-                    indexes.reverse()
-                }
-                indexes
-            }
+            Direction::Down => Either::Left(self.trees.column(col_or_row)),
+            Direction::Up => Either::Right(self.trees.column(col_or_row).rev()),
+            Direction::Right => Either::Left(self.trees.row(col_or_row)),
+            Direction::Left => Either::Right(self.trees.row(col_or_row).rev()),
+            diagonal => unreachable!("the forest is only looked at along rows and columns, not {:?}", diagonal),
         }
     }
 
     fn update_tree_visibility(&mut self) {
+        for (col_or_row, direction) in self.lines_of_sight() {
+            self.update_line(col_or_row, direction);
+        }
+    }
+
+    /// lines_of_sight returns every line the forest is looked at along from the outside: each
+    /// row from both ends, then each column from both ends.
+    fn lines_of_sight(&self) -> Vec<(usize, Direction)> {
+        let mut lines = vec![];
         for row in 0..self.trees.height() {
-            self.update_line(row, ForestDirection::LeftToRight);
-            self.update_line(row, ForestDirection::RightToLeft);
+            lines.push((row, Direction::Right));
+            lines.push((row, Direction::Left));
         }
         for col in 0..self.trees.width() {
-            self.update_line(col, ForestDirection::TopToBottom);
-            self.update_line(col, ForestDirection::BottomToTop);
+            lines.push((col, Direction::Down));
+            lines.push((col, Direction::Up));
         }
+        lines
     }
 
-    fn update_line(&mut self, col_or_row: usize, direction: ForestDirection) {
+    fn update_line(&mut self, col_or_row: usize, direction: Direction) {
         let mut last_tallest_height = -1;
         for idx in self.tree_coordinates(direction, col_or_row) {
             let tree_height = *self.trees.cell(idx).unwrap();
@@ -154,72 +165,191 @@ impl Forest {
                 last_tallest_height = tree_height as i16;
             }
         }
+        log::trace!("looked {:?} along line {}, {} trees visible so far", direction, col_or_row + 1, self.num_visible);
     }
 }
 
 impl Solver for Day8Solver {
-    fn solve_part_1(&self, lines: Vec<String>) -> String {
-        let mut forest = Forest::new(lines);
+    type Input = Forest;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Forest::new(input)
+    }
+
+    fn part1(&self, forest: &Self::Input) -> Result<Answer> {
+        let mut forest = forest.clone();
         forest.update_tree_visibility();
-        let num_visible = forest.tree_is_visible.grid_coordinates().iter().filter(|&c| *forest.tree_is_visible.cell(*c).unwrap()).count();
-        format!("{}", num_visible)
+        let num_visible = forest.tree_is_visible.coordinates().filter(|&c| *forest.tree_is_visible.cell(c).unwrap()).count();
+        Ok(num_visible.into())
     }
 
-    fn solve_part_2(&self, lines: Vec<String>) -> String {
-        let mut forest = Forest::new(lines);
+    fn part2(&self, forest: &Self::Input) -> Result<Answer> {
+        let mut forest = forest.clone();
         forest.update_max_tree_visibility();
         let mut max_visibility = 0;
-        for idx in forest.max_tree_visibility.grid_coordinates() {
+        for idx in forest.max_tree_visibility.coordinates() {
             // Grid requires that we always unwrap, since the entry may not exist (because it's out
             // of bounds). We unwrap one more time because tree visibility may not have been
             // calculated, so it is itself an option.
-            let vis = forest.max_tree_visibility.cell(idx).unwrap().unwrap();
-            let score = vis.top_to_bottom.distance *
-                vis.bottom_to_top.distance *
-                vis.left_to_right.distance *
-                vis.right_to_left.distance;
+            let score = forest.max_tree_visibility.cell(idx).unwrap().unwrap().scenic_score();
             if score > max_visibility {
                 max_visibility = score;
             }
         }
-        format!("{}", max_visibility)
+        Ok(max_visibility.into())
+    }
+}
+
+impl Visualize for Day8Solver {
+    /// Part 1 shows the visible trees as each line of sight is checked. Part 2 shows the tree
+    /// with the best scenic score as each row is searched.
+    fn visualize(&self, part: Part, forest: &Self::Input, frame: &mut dyn FnMut(Frame) -> Result<()>) -> Result<()> {
+        let mut forest = forest.clone();
+        match part {
+            Part::One => {
+                for (col_or_row, direction) in forest.lines_of_sight() {
+                    forest.update_line(col_or_row, direction);
+                    let line = match direction {
+                        Direction::Right => format!("row {} from the left", col_or_row + 1),
+                        Direction::Left => format!("row {} from the right", col_or_row + 1),
+                        Direction::Down => format!("column {} from the top", col_or_row + 1),
+                        Direction::Up => format!("column {} from the bottom", col_or_row + 1),
+                        diagonal => unreachable!("the forest is only looked at along rows and columns, not {:?}", diagonal),
+                    };
+                    let title = format!("looking along {}, {} trees visible", line, forest.num_visible);
+                    frame(Frame::new(title, forest.render_visible()))?;
+                }
+            }
+            Part::Two => {
+                let mut best = (GridCoord::from_row_col(0, 0), 0);
+                for row in 0..forest.trees.height() {
+                    for col in 0..forest.trees.width() {
+                        let coord: GridCoord = GridCoord::from_row_col(row as isize, col as isize);
+                        let score = forest.max_tree_view(coord).scenic_score();
+                        if score > best.1 {
+                            best = (coord, score);
+                        }
+                    }
+                    let title = format!("searched row {}, best scenic score {}", row + 1, best.1);
+                    frame(Frame::new(title, forest.render_best(best.0)))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Explore for Day8Solver {
+    /// The forest, with the trees that are visible from outside it marked.
+    type State = Forest;
+
+    fn explore(&self, forest: &Self::Input) -> Result<Self::State> {
+        let mut forest = forest.clone();
+        forest.update_tree_visibility();
+        Ok(forest)
+    }
+
+    fn commands(&self) -> Vec<Command<Self::State>> {
+        vec![
+            Command::new("tree", "<row> <col>", "the height, visibility and scenic score of a tree", |forest, args| {
+                let coord = forest.tree_at(args)?;
+                let visible = match forest.tree_is_visible.cell(coord).unwrap() {
+                    true => "visible",
+                    false => "hidden",
+                };
+                Ok(format!(
+                    "height {}, {}, scenic score {}",
+                    forest.trees.cell(coord).unwrap(),
+                    visible,
+                    forest.max_tree_view(coord).scenic_score()
+                ))
+            }),
+            Command::new("visible", "", "the forest, with an X for every visible tree", |forest, _| {
+                Ok(format!("{}{} trees are visible", forest.render_visible(), forest.num_visible))
+            }),
+        ]
+    }
+}
+
+impl Generator for Day8Solver {
+    /// The size is the width of the forest. The height is anywhere from half to one and a half
+    /// times the width, so that forests are rarely square.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let width = size.max(1);
+        let height = rng.range((width / 2).max(1)..width + width / 2 + 1);
+        (0..height)
+            .map(|_| {
+                let row: String = (0..width).map(|_| (b'0' + rng.below(10) as u8) as char).collect();
+                row + "\n"
+            })
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod test {
+    use test_case::test_case;
+
     use crate::day08::Day8Solver;
-    use crate::lines_from_file;
+    use crate::inputs::{self, read_input};
+    use crate::repl::{Repl, Reply};
     use crate::solver::Solver;
 
     #[test]
-    fn test_part_1() {
+    fn test_part_1_full() {
         let solver = Day8Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day08.txt");
-        assert_eq!(solver.solve_part_1(lines), "21")
+        let input = solver.parse(&read_input("day08.txt").unwrap()).unwrap();
+        assert_eq!(solver.part1(&input).unwrap(), 1662.into())
     }
 
     #[test]
-    fn test_part_1_full() {
+    fn test_part_2_full() {
         let solver = Day8Solver {};
-        let lines = lines_from_file("./inputs/day08.txt");
-        assert_eq!(solver.solve_part_1(lines), "1662")
+        let input = solver.parse(&read_input("day08.txt").unwrap()).unwrap();
+        assert_eq!(solver.part2(&input).unwrap(), 537600.into())
     }
 
-    #[test]
-    fn test_part_2() {
+    #[test_case("30373\n25512\n65332\n", 14, 2; "wide")]
+    #[test_case("303\n255\n653\n335\n353\n", 14, 2; "tall")]
+    #[test_case("1234\n5678\n", 8, 0; "every tree on the edge")]
+    fn test_non_square_forests(input: &str, visible: usize, scenic_score: usize) {
         let solver = Day8Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day08.txt");
-        assert_eq!(solver.solve_part_2(lines), "8")
+        let forest = solver.parse(input).unwrap();
+        assert_eq!(solver.part1(&forest).unwrap(), visible.into());
+        assert_eq!(solver.part2(&forest).unwrap(), scenic_score.into());
+    }
+
+    #[test_case("", "expected at least one row of trees"; "empty")]
+    #[test_case("123\n4x6\n", "row 2, column 2: expected a digit, found 'x'"; "bad height")]
+    #[test_case("123\n45\n", "row 2: expected 3 cells, found 2"; "ragged")]
+    fn test_bad_forests(input: &str, expected: &str) {
+        assert_eq!(Day8Solver {}.parse(input).err().unwrap().to_string(), expected);
     }
 
     #[test]
-    fn test_part_2_full() {
-        let solver = Day8Solver {};
-        let lines = lines_from_file("./inputs/day08.txt");
-        assert_eq!(solver.solve_part_2(lines), "537600")
+    fn test_repl_on_a_wide_forest() {
+        let mut repl = Repl::new(8, "30373\n25512\n65332\n", None).unwrap();
+        let reply = repl.execute("tree 2 4").unwrap();
+        assert_eq!(reply, Reply::Print("height 1, hidden, scenic score 1".to_string()));
+        assert!(repl.execute("tree 4 2").is_err());
     }
-}
 
+    #[test_case("tree 2 3", "height 5, visible, scenic score 4")]
+    #[test_case("tree 4 3", "height 5, visible, scenic score 8")]
+    #[test_case("tree 3 3", "height 3, hidden, scenic score 1")]
+    #[test_case("tree 1 1", "height 3, visible, scenic score 0"; "edge")]
+    fn test_repl(command: &str, expected: &str) {
+        let mut repl = Repl::new(8, &read_input(inputs::example_input(8)).unwrap(), None).unwrap();
+        assert_eq!(repl.execute(command).unwrap(), Reply::Print(expected.to_string()));
+    }
 
+    #[test]
+    fn test_repl_errors() {
+        let mut repl = Repl::new(8, &read_input(inputs::example_input(8)).unwrap(), None).unwrap();
+        let err = repl.execute("tree 6 1").unwrap_err();
+        assert_eq!(err.to_string(), "there is no tree at row 6, column 1, the forest is 5 by 5");
+        assert!(repl.execute("tree 0 1").is_err());
+        assert!(repl.execute("tree 1").is_err());
+    }
+}
 
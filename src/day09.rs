@@ -1,46 +1,108 @@
-use std::collections::HashSet;
-
-use crate::solver::Solver;
-use crate::utils::grid::{GridCoord};
-
+use color_eyre::eyre::Result;
+use nom::character::complete::{char, one_of};
+use nom::combinator::map_opt;
+use nom::error::context;
+use nom::sequence::separated_pair;
+
+use crate::answer::Answer;
+use crate::generate::Generator;
+use crate::solver::{Part, Solver};
+use crate::utils::grid::{Direction, GridCoord};
+use crate::utils::parse::{parse_lines, unsigned, PResult};
+use crate::utils::rng::Rng;
+use crate::utils::sparse_grid::SparseGrid;
+use crate::visualize::{viewport, Frame, Visualize};
+
+#[derive(Default)]
 pub struct Day9Solver {}
 
 impl Solver for Day9Solver {
-    fn solve_part_1(&self, lines: Vec<String>) -> String {
+    /// The motions of the head of the rope, and how many steps each one takes.
+    type Input = Vec<(Direction, usize)>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let motions: Vec<_> = parse_lines(input, separated_pair(direction, char(' '), unsigned))?;
+        log::debug!("{} motions", motions.len());
+        Ok(motions)
+    }
+
+    fn part1(&self, motions: &Self::Input) -> Result<Answer> {
         let mut rope = Rope::new(2);
-        let uniq_pos = simulate_rope(&mut rope, lines);
-        format!("{}", uniq_pos)
+        let uniq_pos = simulate_rope(&mut rope, motions, |_, _, _| Ok(()))?;
+        Ok(uniq_pos.into())
     }
 
-    fn solve_part_2(&self, lines: Vec<String>) -> String {
+    fn part2(&self, motions: &Self::Input) -> Result<Answer> {
         let mut rope = Rope::new(10);
-        let uniq_pos = simulate_rope(&mut rope, lines);
-        format!("{}", uniq_pos)
+        let uniq_pos = simulate_rope(&mut rope, motions, |_, _, _| Ok(()))?;
+        Ok(uniq_pos.into())
     }
 }
 
-fn simulate_rope(rope: &mut Rope, lines: Vec<String>) -> usize {
-    let mut uniq_pos = HashSet::new();
-    for line in lines {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        let direction = match *parts.first().unwrap() {
-            "R" => RopeMovement::Right,
-            "L" => RopeMovement::Left,
-            "U" => RopeMovement::Up,
-            "D" => RopeMovement::Down,
-            _ => {
-                println!("Invalid direction: {}", parts.first().unwrap());
-                panic!("Invalid direction")
-            }
+impl Visualize for Day9Solver {
+    /// Each frame is a step of the head, showing the knots and the places the tail has been in
+    /// a window that follows the head.
+    fn visualize(&self, part: Part, motions: &Self::Input, frame: &mut dyn FnMut(Frame) -> Result<()>) -> Result<()> {
+        let mut rope = match part {
+            Part::One => Rope::new(2),
+            Part::Two => Rope::new(10),
         };
-        let mut num = parts.get(1).unwrap().parse::<usize>().unwrap();
+        simulate_rope(&mut rope, motions, |idx, rope, visited| {
+            let title = format!(
+                "motion {} of {}, the tail has visited {} positions",
+                idx + 1,
+                motions.len(),
+                visited.len()
+            );
+            let head = rope.knots[0];
+            let body = viewport((head.x, head.y), VIEWPORT_WIDTH, VIEWPORT_HEIGHT, |x, y| {
+                let coord = GridCoord { x, y };
+                // Knots are drawn as H, then 1 to 9, except that the tail of a short rope is a T.
+                match rope.knots.iter().position(|&knot| knot == coord) {
+                    Some(0) => 'H',
+                    Some(_) if rope.length == 2 => 'T',
+                    Some(knot) => char::from_digit(knot as u32, 10).unwrap_or('T'),
+                    None if coord == GridCoord { x: 0, y: 0 } => 's',
+                    None if visited.contains(coord) => '#',
+                    None => '.',
+                }
+            });
+            frame(Frame::new(title, body))
+        })?;
+        Ok(())
+    }
+}
+
+/// The size of the window that follows the head in the visualization.
+const VIEWPORT_WIDTH: usize = 61;
+const VIEWPORT_HEIGHT: usize = 21;
+
+impl Generator for Day9Solver {
+    /// The size is the number of motions of the head.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1))
+            .map(|_| format!("{} {}\n", rng.pick(&['U', 'D', 'L', 'R']), rng.range(1..20)))
+            .collect()
+    }
+}
+
+/// simulate_rope moves the rope through the motions and returns how many places the tail has
+/// been. `after_step` is called after every step of the head, with the index of the motion.
+fn simulate_rope(
+    rope: &mut Rope,
+    motions: &[(Direction, usize)],
+    mut after_step: impl FnMut(usize, &Rope, &SparseGrid<bool>) -> Result<()>,
+) -> Result<usize> {
+    let mut uniq_pos = SparseGrid::new();
+    for (idx, &(direction, mut num)) in motions.iter().enumerate() {
         while num > 0 {
             rope.update(direction);
-            uniq_pos.insert(rope.tail_pos());
+            uniq_pos.insert(rope.tail_pos(), true);
+            after_step(idx, rope, &uniq_pos)?;
             num -= 1;
         }
     }
-    uniq_pos.len()
+    Ok(uniq_pos.len())
 }
 
 struct Rope {
@@ -52,7 +114,7 @@ impl Rope {
     fn new(length: usize) -> Self {
         let mut knots = Vec::with_capacity(length);
         for _ in 0..length {
-            knots.push((0, 0).into());
+            knots.push(GridCoord { x: 0, y: 0 });
         }
         Rope {
             knots,
@@ -60,8 +122,8 @@ impl Rope {
         }
     }
 
-    fn update(&mut self, movement: RopeMovement) {
-        self.knots[0] = self.knots[0] + movement.new_coord();
+    fn update(&mut self, direction: Direction) {
+        self.knots[0] = self.knots[0] + direction.offset();
         for idx in 1..self.knots.len() {
             let current_knot = self.knots[idx];
             let prev_knot = self.knots[idx - 1];
@@ -88,7 +150,7 @@ impl Rope {
                 0 => 0,
                 _ => dy / dy.abs(),
             };
-            self.knots[idx] = current_knot + (new_dx, new_dy).into();
+            self.knots[idx] = current_knot + GridCoord { x: new_dx, y: new_dy };
         }
     }
 
@@ -114,66 +176,52 @@ fn is_touching(head: GridCoord, tail: GridCoord) -> bool {
     false
 }
 
-#[derive(Clone, Copy)]
-enum RopeMovement {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-
-impl RopeMovement {
-    fn new_coord(&self) -> GridCoord {
-        match self {
-            RopeMovement::Left => { (-1, 0).into() }
-            RopeMovement::Right => { (1, 0).into() }
-            RopeMovement::Up => { (0, -1).into() }
-            RopeMovement::Down => { (0, 1).into() }
-        }
-    }
+/// direction parses the direction of a motion of the head.
+fn direction(i: &str) -> PResult<'_, Direction> {
+    context("a direction (U, D, L or R)", map_opt(one_of("UDLR"), Direction::from_char))(i)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day09::Day9Solver;
-    use crate::lines_from_file;
+    use crate::day09::{simulate_rope, Day9Solver, Rope};
+    use crate::inputs::read_input;
     use crate::solver::Solver;
 
     #[test]
-    fn test_part_1() {
+    fn test_part_1_full() {
         let solver = Day9Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day09.txt");
-        assert_eq!(solver.solve_part_1(lines), "13")
+        let input = solver.parse(&read_input("day09.txt").unwrap()).unwrap();
+        assert_eq!(solver.part1(&input).unwrap(), 6494.into())
     }
 
     #[test]
-    fn test_part_1_full() {
+    fn test_part_2_full() {
         let solver = Day9Solver {};
-        let lines = lines_from_file("./inputs/day09.txt");
-        assert_eq!(solver.solve_part_1(lines), "6494")
+        let input = solver.parse(&read_input("day09.txt").unwrap()).unwrap();
+        assert_eq!(solver.part2(&input).unwrap(), 2691.into())
     }
 
     #[test]
-    fn test_part_2() {
-        let solver = Day9Solver {};
-        let lines: Vec<String> = vec![
-            "R 5",
-            "U 8",
-            "L 8",
-            "D 3",
-            "R 17",
-            "D 10",
-            "L 25",
-            "U 20",
-        ].iter().map(|s| s.to_string()).collect();
-        assert_eq!(solver.solve_part_2(lines), "36")
+    fn test_tail_positions() {
+        let motions = Day9Solver {}.parse("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2\n").unwrap();
+        let mut visited = String::new();
+        let count = simulate_rope(&mut Rope::new(2), &motions, |_, _, positions| {
+            visited = positions.to_string();
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(count, 13);
+        assert_eq!(visited, "..##.\n...##\n.####\n....#\n####.\n");
     }
 
     #[test]
-    fn test_part_2_full() {
+    fn test_invalid_direction() {
         let solver = Day9Solver {};
-        let lines = lines_from_file("./inputs/day09.txt");
-        assert_eq!(solver.solve_part_2(lines), "2691")
+        let err = solver.parse("R 4\nX 2\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: expected a direction (U, D, L or R)\n  |\n2 | X 2\n  | ^"
+        );
     }
 }
 
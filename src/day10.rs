@@ -1,19 +1,36 @@
 use std::collections::VecDeque;
 
-use nom::{Finish, IResult};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::combinator::{all_consuming, map, value};
+use nom::combinator::{map, value};
+use nom::error::context;
 use nom::sequence::preceded;
+use color_eyre::eyre::{eyre, Result};
 
-use crate::solver::Solver;
+use crate::answer::Answer;
+use crate::generate::Generator;
+use crate::repl::{arg, Command, Explore};
+use crate::solver::{Part, Solver};
+use crate::utils::grid::{CellChar, Grid, GridCoord};
+use crate::utils::parse::{parse_lines, signed, PResult};
+use crate::utils::rng::Rng;
+use crate::visualize::{Frame, Visualize};
 
+#[derive(Default)]
 pub struct Day10Solver;
 
 impl Solver for Day10Solver {
-    fn solve_part_1(&self, lines: Vec<String>) -> String {
-        let instructions: VecDeque<Instruction> = lines.iter().map(|l| all_consuming(Instruction::parse)(l).finish().unwrap().1).collect();
-        let mut cpu = Cpu::new(instructions);
+    /// The program the CPU will run.
+    type Input = VecDeque<Instruction>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let instructions = parse_lines(input, Instruction::parse)?;
+        log::debug!("{} instructions", instructions.len());
+        Ok(instructions.into())
+    }
+
+    fn part1(&self, instructions: &Self::Input) -> Result<Answer> {
+        let mut cpu = Cpu::new(instructions.clone());
         let mut signal = 0i32;
         while cpu.advance() {
             let cycle = cpu.read_next_cycle();
@@ -24,27 +41,115 @@ impl Solver for Day10Solver {
                 _ => {}
             }
         }
-        format!("{}", signal)
+        Ok(signal.into())
     }
 
-    fn solve_part_2(&self, lines: Vec<String>) -> String {
-        let instructions: VecDeque<Instruction> = lines.iter().map(|l| all_consuming(Instruction::parse)(l).finish().unwrap().1).collect();
-        let mut cpu = Cpu::new(instructions);
-        let mut crt = Crt::new();
-        while cpu.advance() {
-            let register = cpu.read_register();
-            // The CPU reports the next cycle that it will execute
-            let cycle = cpu.read_last_finished_cycle();
-            let col_pixel_col = cycle % 40;
-            if (register - (col_pixel_col as i32)).abs() <= 1 {
-                crt.set_pixel(cycle);
+    fn part2(&self, instructions: &Self::Input) -> Result<Answer> {
+        let crt = draw(instructions, |_, _, _| Ok(()))?;
+        Ok(crt.bitmap().into())
+    }
+}
+
+/// draw runs the program and lights the pixels of the CRT that the sprite covers as the beam
+/// passes. `after_cycle` is called after every cycle, with the cycle and the register.
+fn draw(instructions: &VecDeque<Instruction>, mut after_cycle: impl FnMut(usize, i32, &Crt) -> Result<()>) -> Result<Crt> {
+    let mut cpu = Cpu::new(instructions.clone());
+    let mut crt = Crt::new();
+    while cpu.advance() {
+        let register = cpu.read_register();
+        // The CPU reports the next cycle that it will execute
+        let cycle = cpu.read_last_finished_cycle();
+        let col_pixel_col = cycle % 40;
+        if (register - (col_pixel_col as i32)).abs() <= 1 {
+            crt.set_pixel(cycle)?;
+        }
+        after_cycle(cycle, register, &crt)?;
+    }
+    Ok(crt)
+}
+
+impl Visualize for Day10Solver {
+    /// Each frame is a cycle, showing the sprite above the screen and the beam as a @. Part 1
+    /// also keeps a running total of the signal strength.
+    fn visualize(&self, part: Part, instructions: &Self::Input, frame: &mut dyn FnMut(Frame) -> Result<()>) -> Result<()> {
+        let mut signal = 0i32;
+        draw(instructions, |cycle, register, crt| {
+            // Part 1 counts cycles from 1, and looks at the register during the cycle.
+            let cycle_number = cycle + 1;
+            if cycle_number % 40 == 20 {
+                signal += cycle_number as i32 * register;
+            }
+            let title = match part {
+                Part::One => format!("cycle {}: X = {}, signal strength {}", cycle_number, register, signal),
+                Part::Two => format!("cycle {}: X = {}", cycle_number, register),
+            };
+            let sprite: String = (0..CRT_WIDTH as i32)
+                .map(|col| if (register - col).abs() <= 1 { '=' } else { ' ' })
+                .collect();
+            frame(Frame::new(title, sprite + "\n" + &crt.render(cycle)))
+        })?;
+        Ok(())
+    }
+}
+
+impl Explore for Day10Solver {
+    /// The CPU, which the commands step through the program.
+    type State = Cpu;
+
+    fn explore(&self, instructions: &Self::Input) -> Result<Self::State> {
+        Ok(Cpu::new(instructions.clone()))
+    }
+
+    fn commands(&self) -> Vec<Command<Self::State>> {
+        vec![
+            Command::new("step", "[cycles]", "run the CPU for some cycles, one by default", |cpu, args| {
+                let cycles = if args.is_empty() { 1 } else { arg(args, 0, "cycles")? };
+                for _ in 0..cycles {
+                    if !cpu.advance() {
+                        return Ok(format!("the program finished after cycle {}", cpu.read_next_cycle()));
+                    }
+                }
+                Ok(cpu.describe())
+            }),
+            Command::new("until", "<cycle>", "run the CPU until the given cycle", |cpu, args| {
+                let cycle: usize = arg(args, 0, "cycle")?;
+                if cycle <= cpu.read_next_cycle() {
+                    return Err(eyre!("the CPU is already at cycle {}, reload to start over", cpu.read_next_cycle()));
+                }
+                while cpu.read_next_cycle() < cycle {
+                    if !cpu.advance() {
+                        return Ok(format!("the program finished after cycle {}", cpu.read_next_cycle()));
+                    }
+                }
+                Ok(cpu.describe())
+            }),
+        ]
+    }
+}
+
+impl Generator for Day10Solver {
+    /// The size is ignored: the program always runs for exactly as many cycles as the CRT has
+    /// pixels, since drawing past the last one is an error. The register stays on the screen.
+    fn generate(&self, rng: &mut Rng, _size: usize) -> String {
+        let mut output = String::new();
+        let (mut cycles, mut register) = (0, 1i32);
+        while cycles < CRT_WIDTH * CRT_HEIGHT {
+            if cycles + 1 == CRT_WIDTH * CRT_HEIGHT || rng.chance(0.3) {
+                output += "noop\n";
+                cycles += 1;
+            } else {
+                let target = (register + rng.range(0..21) as i32 - 10).clamp(0, CRT_WIDTH as i32 - 1);
+                output += &format!("addx {}\n", target - register);
+                register = target;
+                cycles += 2;
             }
         }
-        crt.display()
+        output
     }
 }
 
-struct Cpu {
+/// Cpu runs the program one cycle at a time.
+pub struct Cpu {
     register: i32,
     cycle_num: usize,
     instructions: VecDeque<Instruction>,
@@ -53,37 +158,45 @@ struct Cpu {
     instructions_completed: usize,
 }
 
+const CRT_WIDTH: usize = 40;
+const CRT_HEIGHT: usize = 6;
+
 struct Crt {
-    pixels: Vec<bool>,
+    pixels: Grid<bool>,
 }
 
 impl Crt {
     fn new() -> Self {
         Self {
-            pixels: vec![false; 240],
+            pixels: Grid::new(CRT_WIDTH, CRT_HEIGHT),
         }
     }
 
-    fn set_pixel(&mut self, idx: usize) {
-        self.pixels[idx] = true
+    fn set_pixel(&mut self, idx: usize) -> Result<()> {
+        let pixel = self
+            .pixels
+            .cell_mut(pixel_coord(idx))
+            .ok_or_else(|| eyre!("cycle {} is past the last of the {} pixels on the CRT", idx, CRT_WIDTH * CRT_HEIGHT))?;
+        *pixel = true;
+        Ok(())
     }
 
-    fn display(&self) -> String {
-        let mut out = "".to_string();
-        for row in 0..6 {
-            let mut pixel_row = "".to_string();
-            for col in 0..40 {
-                pixel_row += match self.pixels[row * 40 + col] {
-                    true => { "#" }
-                    false => { "." }
-                };
-            }
-            out = format!("{}{}\n", out, pixel_row);
-        }
-        out
+    /// render draws the screen with the beam at the given pixel.
+    fn render(&self, beam: usize) -> String {
+        let beam = pixel_coord(beam);
+        self.pixels.render_with(|coord, lit| if coord == beam { '@' } else { lit.to_char() })
+    }
+
+    fn bitmap(&self) -> Grid<bool> {
+        self.pixels.clone()
     }
 }
 
+/// pixel_coord returns where the pixel drawn at the given cycle, counted from 0, is on the CRT.
+fn pixel_coord(idx: usize) -> GridCoord {
+    GridCoord::from_row_col((idx / CRT_WIDTH) as isize, (idx % CRT_WIDTH) as isize)
+}
+
 impl Cpu {
     fn new(instructions: VecDeque<Instruction>) -> Self {
         Self {
@@ -101,7 +214,7 @@ impl Cpu {
     // advance will advance the CPU by one cycle and returns an indicator as to whether it
     // can still continue
     fn advance(&mut self) -> bool {
-        if self.instructions.len() == 0 {
+        if self.instructions.is_empty() {
             return false;
         }
         // Start the cycle.
@@ -123,6 +236,7 @@ impl Cpu {
                 self.register += x;
             }
         }
+        log::trace!("cycle {}: finished {:?}, X = {}", self.cycle_num, self.current_instruction, self.register);
         self.current_instruction = self.instructions.pop_front().unwrap();
 
         // Set the timers for when it will finish
@@ -146,77 +260,79 @@ impl Cpu {
     fn read_last_finished_cycle(&self) -> usize {
         self.cycle_num - 1
     }
+
+    /// describe says which cycle the CPU is in, and the register and signal strength during it.
+    fn describe(&self) -> String {
+        let (cycle, register) = (self.read_next_cycle(), self.read_register());
+        format!("cycle {}: X = {}, signal strength {}", cycle, register, cycle as i32 * register)
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
-enum Instruction {
+pub enum Instruction {
     Noop,
     AddX(i32),
 }
 
 impl Instruction {
-    fn parse(i: &str) -> IResult<&str, Self> {
+    fn parse(i: &str) -> PResult<'_, Self> {
         let noop = tag("noop");
-        let addx = preceded(tag("addx "), nom::character::complete::i32);
-        alt((
-            // If we match Noop, just return the Noop variant
-            value(Self::Noop, noop),
-            // If preceded by addx, apply the complete i32 parser to it and pass it to Self::AddX
-            map(addx, Self::AddX))
+        let addx = preceded(tag("addx "), signed);
+        context(
+            "an instruction (noop or addx)",
+            alt((
+                // If we match Noop, just return the Noop variant
+                value(Self::Noop, noop),
+                // If preceded by addx, apply the signed number parser to it and pass it to Self::AddX
+                map(addx, Self::AddX),
+            )),
         )(i)
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::answer::Answer;
     use crate::day10::Day10Solver;
-    use crate::lines_from_file;
+    use crate::inputs::{self, read_input};
+    use crate::repl::{Repl, Reply};
     use crate::solver::Solver;
 
-    #[test]
-    fn test_part_1() {
-        let solver = Day10Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day10.txt");
-        assert_eq!(solver.solve_part_1(lines), "13140")
-    }
-
     #[test]
     fn test_part_1_full() {
         let solver = Day10Solver {};
-        let lines = lines_from_file("./inputs/day10.txt");
-        assert_eq!(solver.solve_part_1(lines), "14760")
-    }
-
-    #[test]
-    fn test_part_2() {
-        let solver = Day10Solver {};
-        let lines = lines_from_file("./inputs/unit_test/day10.txt");
-        let msg = vec![
-            "##..##..##..##..##..##..##..##..##..##..",
-            "###...###...###...###...###...###...###.",
-            "####....####....####....####....####....",
-            "#####.....#####.....#####.....#####.....",
-            "######......######......######......####",
-            "#######.......#######.......#######.....",
-        ].join("\n") + "\n";
-        assert_eq!(solver.solve_part_2(lines), msg)
+        let input = solver.parse(&read_input("day10.txt").unwrap()).unwrap();
+        assert_eq!(solver.part1(&input).unwrap(), 14760.into())
     }
 
     #[test]
     fn test_part_2_full() {
         let solver = Day10Solver {};
-        let lines = lines_from_file("./inputs/day10.txt");
+        let input = solver.parse(&read_input("day10.txt").unwrap()).unwrap();
         // EFGERURE
-        let msg = vec![
+        let msg = [
             "####.####..##..####.###..#..#.###..####.",
             "#....#....#..#.#....#..#.#..#.#..#.#....",
             "###..###..#....###..#..#.#..#.#..#.###..",
             "#....#....#.##.#....###..#..#.###..#....",
             "#....#....#..#.#....#.#..#..#.#.#..#....",
             "####.#.....###.####.#..#..##..#..#.####.",
-        ].join("\n") + "\n";
-        assert_eq!(solver.solve_part_2(lines), msg)
+        ];
+        assert_eq!(solver.part2(&input).unwrap(), Answer::bitmap_from_rows(&msg).unwrap())
     }
-}
-
 
+    #[test]
+    fn test_repl() {
+        let mut repl = Repl::new(10, &read_input(inputs::example_input(10)).unwrap(), None).unwrap();
+        let mut print = |command| match repl.execute(command).unwrap() {
+            Reply::Print(output) => output,
+            Reply::Quit => panic!("{command:?} quit"),
+        };
+        assert_eq!(print("step"), "cycle 1: X = 1, signal strength 1");
+        assert_eq!(print("step 2"), "cycle 3: X = 16, signal strength 48");
+        assert_eq!(print("until 20"), "cycle 20: X = 21, signal strength 420");
+        assert_eq!(print("until 220"), "cycle 220: X = 18, signal strength 3960");
+        assert_eq!(print("step 100"), "the program finished after cycle 240");
+        assert!(repl.execute("until 20").is_err());
+    }
+}
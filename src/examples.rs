@@ -0,0 +1,124 @@
+//! The examples from the puzzle descriptions, along with their answers.
+//!
+//! Examples live in the `unit_test` directory of the inputs. The main example for a day is
+//! `dayNN.txt`, and any others are named `dayNN-NAME.txt`. Next to each example is a sidecar
+//! file, `dayNN.json` or `dayNN-NAME.json`, holding the answers the puzzle gives for it:
+//!
+//! ```json
+//! {"part1": 13, "part2": 1}
+//! ```
+//!
+//! A part without an answer is not checked, since some examples only apply to one part.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use serde::Deserialize;
+
+use crate::answer::Answer;
+use crate::answers::ExpectedAnswers;
+use crate::inputs::{Inputs, EXAMPLES_DIR};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Sidecar {
+    #[serde(default)]
+    part1: Option<Answer>,
+    #[serde(default)]
+    part2: Option<Answer>,
+}
+
+/// discover finds every example and reads its answers. The examples are ordered by day, with
+/// each day's main example first. An example without a sidecar file is an error, so it can't be
+/// skipped by accident.
+pub fn discover(inputs: &Inputs) -> Result<Vec<ExpectedAnswers>> {
+    let dir = inputs.find(EXAMPLES_DIR)?;
+    let mut examples = vec![];
+    for entry in fs::read_dir(&dir).wrap_err_with(|| format!("could not list {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+        let Some((day, name)) = path.file_stem().and_then(|s| s.to_str()).and_then(example_name) else {
+            continue;
+        };
+        let sidecar = path.with_extension("json");
+        let answers = read_sidecar(&sidecar)?;
+        if answers.part1.is_none() && answers.part2.is_none() {
+            return Err(eyre!("{} has no answers", sidecar.display()));
+        }
+        let file_name = PathBuf::from(path.file_name().expect("the path has a stem"));
+        examples.push((
+            (day, name.map(str::to_string)),
+            ExpectedAnswers {
+                day,
+                input: Path::new(EXAMPLES_DIR).join(file_name),
+                part1: answers.part1,
+                part2: answers.part2,
+            },
+        ));
+    }
+    examples.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(examples.into_iter().map(|(_, example)| example).collect())
+}
+
+/// example_name splits `dayNN` or `dayNN-NAME` into the day and the name, if there is one.
+fn example_name(stem: &str) -> Option<(u8, Option<&str>)> {
+    let rest = stem.strip_prefix("day")?;
+    let (day, name) = match rest.split_once('-') {
+        Some((day, name)) if !name.is_empty() => (day, Some(name)),
+        Some(_) => return None,
+        None => (rest, None),
+    };
+    if day.len() != 2 {
+        return None;
+    }
+    Some((day.parse().ok()?, name))
+}
+
+fn read_sidecar(path: &Path) -> Result<Sidecar> {
+    let contents = fs::read_to_string(path)
+        .wrap_err_with(|| format!("could not read {}, which should hold the example's answers", path.display()))?;
+    serde_json::from_str(&contents).wrap_err_with(|| format!("could not parse {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::solver::Part;
+
+    #[test]
+    fn test_example_name() {
+        assert_eq!(example_name("day06"), Some((6, None)));
+        assert_eq!(example_name("day06-2"), Some((6, Some("2"))));
+        assert_eq!(example_name("day06-"), None);
+        assert_eq!(example_name("day6"), None);
+        assert_eq!(example_name("notes"), None);
+    }
+
+    #[test]
+    fn test_discover() {
+        let examples = discover(&Inputs::new(None)).unwrap();
+        let day6: Vec<_> = examples.iter().filter(|e| e.day == 6).collect();
+        assert_eq!(day6.len(), 5);
+        assert_eq!(day6[0].input, Path::new("unit_test/day06.txt"));
+        assert_eq!(day6[0].get(Part::One), Some(&Answer::from(7)));
+
+        let larger = examples.iter().find(|e| e.input.ends_with("day09-larger.txt")).unwrap();
+        assert_eq!(larger.get(Part::One), None);
+        assert_eq!(larger.get(Part::Two), Some(&Answer::from(36)));
+    }
+
+    #[test]
+    fn test_discover_requires_sidecars() {
+        let dir = std::env::temp_dir().join(format!("aoc-examples-{}", std::process::id()));
+        fs::create_dir_all(dir.join(EXAMPLES_DIR)).unwrap();
+        fs::write(dir.join(EXAMPLES_DIR).join("day01.txt"), "1\n").unwrap();
+
+        let err = discover(&Inputs::with_roots(vec![dir.clone()])).unwrap_err();
+        assert!(format!("{:#}", err).contains("day01.json"), "{:#}", err);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+//! Random puzzle inputs, for stress testing and benchmarking the solvers on inputs much larger
+//! than the real ones.
+//!
+//! Every day implements `Generator`, and `register_solvers!` won't build without it. An input is
+//! determined by its seed and size, so a failure found with a generated input can be reproduced
+//! with `generate <day> --seed S --size N`.
+
+use crate::utils::rng::Rng;
+
+/// Generator produces random inputs that the day's solver is guaranteed to solve, in the same
+/// format as the real input.
+pub trait Generator {
+    /// generate returns an input of roughly the given size. What the size counts depends on the
+    /// day, such as lines, moves or characters, and is documented on each implementation.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String;
+}
+
+/// The size used when none is asked for, small enough to generate and solve in a blink.
+pub const DEFAULT_SIZE: usize = 1000;
+
+#[cfg(test)]
+mod test {
+    use crate::solver::Part;
+    use crate::SOLVERS;
+
+    #[test]
+    fn test_generated_inputs_are_solvable() {
+        for registration in SOLVERS {
+            let solver = registration.solver();
+            for seed in 0..10 {
+                for size in [1, 10, 200] {
+                    let input = registration.generate(seed, size);
+                    let parsed = solver.parse(&input).unwrap_or_else(|e| {
+                        panic!("day {} seed {} size {}: {:#}\n{}", registration.day, seed, size, e, input)
+                    });
+                    for part in Part::ALL {
+                        if let Err(e) = solver.solve(part, parsed.as_ref()) {
+                            panic!("day {} part {} seed {} size {}: {:#}", registration.day, part, seed, size, e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs_depend_on_the_seed() {
+        for registration in SOLVERS {
+            let input = registration.generate(3, 100);
+            assert_eq!(registration.generate(3, 100), input, "day {}", registration.day);
+            assert_ne!(registration.generate(4, 100), input, "day {}", registration.day);
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs_grow_with_the_size() {
+        for registration in SOLVERS {
+            let small = registration.generate(0, 10);
+            let large = registration.generate(0, 1000);
+            // The CRT program of day 10 always takes the same number of cycles.
+            if registration.day != 10 {
+                assert!(large.len() > small.len(), "day {}", registration.day);
+            }
+        }
+    }
+}
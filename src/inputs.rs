@@ -0,0 +1,107 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result};
+
+/// The environment variable that points at the inputs directory when `--inputs` isn't given.
+pub const INPUTS_ENV: &str = "AOC_INPUTS";
+
+/// Inputs finds puzzle input files by name, such as `day05.txt` or `unit_test/day05.txt`, so
+/// that nothing depends on the directory the binary or the tests are run from.
+///
+/// Each root is searched in order and the first one holding the file wins. The roots are the
+/// directory given on the command line, then the one in `AOC_INPUTS`, then the `inputs`
+/// directory of this crate.
+#[derive(Debug, Clone)]
+pub struct Inputs {
+    roots: Vec<PathBuf>,
+}
+
+impl Inputs {
+    /// new searches the given root, if any, before the roots every search falls back to.
+    pub fn new(root: Option<PathBuf>) -> Self {
+        let from_env = env::var_os(INPUTS_ENV).filter(|v| !v.is_empty()).map(PathBuf::from);
+        Inputs::with_roots(root.into_iter().chain(from_env).chain([Inputs::crate_root()]).collect())
+    }
+
+    /// with_roots searches exactly the given roots, ignoring the environment.
+    pub fn with_roots(roots: Vec<PathBuf>) -> Self {
+        Inputs { roots }
+    }
+
+    /// crate_root is the `inputs` directory checked in next to the sources.
+    pub fn crate_root() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs")
+    }
+
+    /// find returns the path of the first file or directory with the given name under one of the
+    /// roots. The error lists every path that was tried.
+    pub fn find(&self, name: impl AsRef<Path>) -> Result<PathBuf> {
+        let name = name.as_ref();
+        let candidates: Vec<PathBuf> = self.roots.iter().map(|root| root.join(name)).collect();
+        candidates.iter().find(|path| path.exists()).cloned().ok_or_else(|| {
+            let searched: Vec<String> = candidates.iter().map(|p| format!("  {}", p.display())).collect();
+            eyre!("could not find the input {}, searched:\n{}", name.display(), searched.join("\n"))
+        })
+    }
+
+    /// read returns the contents of the first file with the given name under one of the roots.
+    pub fn read(&self, name: impl AsRef<Path>) -> Result<String> {
+        crate::input_from_file(self.find(name)?)
+    }
+}
+
+/// day_input is the name of a day's real input.
+pub fn day_input(day: u8) -> PathBuf {
+    PathBuf::from(format!("day{:02}.txt", day))
+}
+
+/// The directory under an inputs root that holds the examples from the puzzle descriptions.
+pub const EXAMPLES_DIR: &str = "unit_test";
+
+/// example_input is the name of a day's example input, from the puzzle description.
+pub fn example_input(day: u8) -> PathBuf {
+    PathBuf::from(EXAMPLES_DIR).join(format!("day{:02}.txt", day))
+}
+
+/// read_input reads an input by name from the default roots. It is what tests use to load the
+/// checked-in inputs.
+pub fn read_input(name: impl AsRef<Path>) -> Result<String> {
+    Inputs::new(None).read(name)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_crate_root_holds_every_input() {
+        let inputs = Inputs::with_roots(vec![Inputs::crate_root()]);
+        for registration in crate::SOLVERS {
+            assert!(inputs.find(day_input(registration.day)).is_ok());
+            assert!(inputs.find(example_input(registration.day)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_earlier_roots_win() {
+        let dir = env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day01.txt"), "1\n").unwrap();
+
+        let inputs = Inputs::with_roots(vec![dir.clone(), Inputs::crate_root()]);
+        assert_eq!(inputs.find("day01.txt").unwrap(), dir.join("day01.txt"));
+        assert_eq!(inputs.find("day02.txt").unwrap(), Inputs::crate_root().join("day02.txt"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_inputs_list_the_paths_searched() {
+        let inputs = Inputs::with_roots(vec![PathBuf::from("/nowhere"), Inputs::crate_root()]);
+        let err = format!("{:#}", inputs.find("day99.txt").unwrap_err());
+        assert!(err.contains(&Path::new("/nowhere").join("day99.txt").display().to_string()), "{err}");
+        assert!(err.contains(&Inputs::crate_root().join("day99.txt").display().to_string()), "{err}");
+    }
+}
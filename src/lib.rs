@@ -0,0 +1,121 @@
+//! Solutions to Advent of Code 2022, and the tooling to run, check and time them.
+//!
+//! Every day is registered in [`SOLVERS`], which is the entry point for anything that wants to
+//! run the solvers. The binary in `main.rs` is a thin command-line wrapper around this crate.
+
+use std::{
+    fs::File,
+    io::prelude::*,
+    path::Path,
+};
+
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::solver::DynSolver;
+
+#[macro_use]
+pub mod solver;
+pub mod allocations;
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod compare;
+pub mod examples;
+pub mod generate;
+pub mod inputs;
+pub mod logging;
+pub mod output;
+pub mod repl;
+pub mod runner;
+pub mod utils;
+pub mod visualize;
+
+register_solvers! {
+    day01::Day1Solver,
+    day02::Day2Solver,
+    day03::Day3Solver,
+    day04::Day4Solver,
+    day05::Day5Solver,
+    day06::Day6Solver,
+    day07::Day7Solver,
+    day08::Day8Solver,
+    day09::Day9Solver,
+    day10::Day10Solver,
+}
+
+register_visualizers! {
+    day05::Day5Solver,
+    day08::Day8Solver,
+    day09::Day9Solver,
+    day10::Day10Solver,
+}
+
+register_explorers! {
+    day07::Day7Solver,
+    day08::Day8Solver,
+    day10::Day10Solver,
+}
+
+/// get_solver returns the solver registered for the given day, if there is one.
+pub fn get_solver(day: u8) -> Option<Box<dyn DynSolver>> {
+    SOLVERS.iter().find(|r| r.day == day).map(|r| r.solver())
+}
+
+/// Returns the whole contents of a puzzle input file. Solvers parse the input themselves, so
+/// tests can just as well hand them a string literal.
+pub fn input_from_file(filename: impl AsRef<Path>) -> Result<String> {
+    let filename = filename.as_ref();
+    let file = File::open(filename)
+        .wrap_err_with(|| format!("could not open {}", filename.display()))?;
+    input_from_reader(file).wrap_err_with(|| format!("could not read {}", filename.display()))
+}
+
+/// Returns everything that can be read from a reader, such as stdin.
+pub fn input_from_reader(mut reader: impl Read) -> Result<String> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    Ok(input)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_every_day_module_is_registered() {
+        let registered: HashSet<&str> = SOLVERS.iter().map(|r| r.module).collect();
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut found = 0;
+        for entry in fs::read_dir(src).unwrap() {
+            let name = entry.unwrap().file_name().into_string().unwrap();
+            let Some(module) = name.strip_suffix(".rs") else {
+                continue;
+            };
+            if module.starts_with("day") {
+                found += 1;
+                assert!(registered.contains(module), "{module} is not listed in register_solvers!");
+            }
+        }
+        assert_eq!(found, SOLVERS.len());
+    }
+
+    #[test]
+    fn test_days_are_unique_and_in_order() {
+        let days: Vec<u8> = SOLVERS.iter().map(|r| r.day).collect();
+        let mut sorted = days.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(days, sorted);
+    }
+
+    #[test]
+    fn test_get_solver() {
+        for registration in SOLVERS {
+            assert!(get_solver(registration.day).is_some());
+        }
+        assert!(get_solver(0).is_none());
+    }
+}
@@ -0,0 +1,155 @@
+//! A small logger for the `log` macros, which writes to stderr so that stdout only has answers.
+//!
+//! What gets logged is set by a filter like `info` or `warn,day07=debug`: a default level, and
+//! levels for modules. Modules are named by their path in this crate, such as `day07` or
+//! `utils::parse`, and a module's level applies to everything under it. The most specific
+//! module wins. The filter comes from `--log`, then from `AOC_LOG`, and is `warn` otherwise.
+
+use std::env;
+use std::io::Write;
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Report, Result};
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// The environment variable holding the log filter when `--log` isn't given.
+pub const LOG_ENV: &str = "AOC_LOG";
+
+/// The filter used when none is given.
+pub const DEFAULT_FILTER: &str = "warn";
+
+/// Filter decides which records are logged, by level and module.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    default: LevelFilter,
+    /// Modules and their levels, most specific first.
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    /// level returns the most verbose level logged for a target, such as `rust_aoc2022::day07`.
+    pub fn level(&self, target: &str) -> LevelFilter {
+        let path = target.strip_prefix(concat!(env!("CARGO_CRATE_NAME"), "::")).unwrap_or(target);
+        self.modules
+            .iter()
+            .find(|(module, _)| {
+                [path, target]
+                    .iter()
+                    .any(|t| t.strip_prefix(module.as_str()).is_some_and(|rest| rest.is_empty() || rest.starts_with("::")))
+            })
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    /// max_level is the most verbose level logged for any target.
+    pub fn max_level(&self) -> LevelFilter {
+        self.modules.iter().map(|&(_, level)| level).chain([self.default]).max().unwrap_or(LevelFilter::Off)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter { default: LevelFilter::Warn, modules: vec![] };
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let parse_level = |level: &str| {
+                LevelFilter::from_str(level).map_err(|_| {
+                    eyre!("invalid log level {:?}, expected off, error, warn, info, debug or trace", level)
+                })
+            };
+            match directive.split_once('=') {
+                Some((module, level)) => filter.modules.push((module.trim().to_string(), parse_level(level.trim())?)),
+                None => filter.default = parse_level(directive)?,
+            }
+        }
+        // Longer paths are more specific, so they are checked first.
+        filter.modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        Ok(filter)
+    }
+}
+
+struct Logger {
+    filter: Filter,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            // Logging is best effort, there is nowhere to report a failure to write to stderr.
+            let _ = writeln!(std::io::stderr().lock(), "{}", format_record(record));
+        }
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stderr().flush();
+    }
+}
+
+/// format_record formats a record as `[LEVEL module] message`.
+fn format_record(record: &Record) -> String {
+    let target = record.target();
+    let module = target.strip_prefix(concat!(env!("CARGO_CRATE_NAME"), "::")).unwrap_or(target);
+    format!("[{:<5} {}] {}", record.level(), module, record.args())
+}
+
+/// init installs the logger, with the filter given on the command line, in `AOC_LOG`, or the
+/// default one. It can only be called once.
+pub fn init(filter: Option<&str>) -> Result<()> {
+    let from_env = env::var(LOG_ENV).ok().filter(|f| !f.is_empty());
+    let spec = filter.map(str::to_string).or(from_env).unwrap_or_else(|| DEFAULT_FILTER.to_string());
+    let filter: Filter = spec.parse()?;
+    log::set_max_level(filter.max_level());
+    log::set_boxed_logger(Box::new(Logger { filter })).map_err(|e| eyre!("could not install the logger: {}", e))
+}
+
+#[cfg(test)]
+mod test {
+    use log::Level;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("rust_aoc2022::day07", LevelFilter::Debug)]
+    #[test_case("rust_aoc2022::day07::tree", LevelFilter::Debug; "submodule")]
+    #[test_case("rust_aoc2022::day070", LevelFilter::Info; "module with the same prefix")]
+    #[test_case("rust_aoc2022::utils::parse", LevelFilter::Trace)]
+    #[test_case("rust_aoc2022::utils::grid", LevelFilter::Off)]
+    #[test_case("indextree", LevelFilter::Info)]
+    fn test_filter_levels(target: &str, expected: LevelFilter) {
+        let filter: Filter = "info,day07=debug,utils=off,utils::parse=trace".parse().unwrap();
+        assert_eq!(filter.level(target), expected);
+    }
+
+    #[test]
+    fn test_filter_defaults_to_warn() {
+        let filter: Filter = "day05=trace".parse().unwrap();
+        assert_eq!(filter.level("rust_aoc2022::day01"), LevelFilter::Warn);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+        assert_eq!("".parse::<Filter>().unwrap().max_level(), LevelFilter::Warn);
+    }
+
+    #[test]
+    fn test_filter_accepts_full_paths() {
+        let filter: Filter = "rust_aoc2022::day07=trace".parse().unwrap();
+        assert_eq!(filter.level("rust_aoc2022::day07"), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_bad_filters() {
+        let err = "loud".parse::<Filter>().unwrap_err();
+        assert!(err.to_string().contains("invalid log level \"loud\""), "{err}");
+        assert!("day07=".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn test_format_record() {
+        let format = |args| {
+            format_record(&Record::builder().level(Level::Debug).target("rust_aoc2022::day07").args(args).build())
+        };
+        assert_eq!(format(format_args!("{} directories", 3)), "[DEBUG day07] 3 directories");
+    }
+}
@@ -1,62 +1,482 @@
 use std::{
-    fs::File,
-    io::{BufReader, prelude::*},
-    path::Path,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
-use std::env;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod solver;
-mod utils;
-
-fn main() {
-    // Read from stdin
-    let args: Vec<String> = env::args().collect();
-
-    // Parse first argument as a uint8
-    let day: u8 = args[1].parse().unwrap();
-
-    let solver = get_solver(day).unwrap();
-    // read file contents as an array of lines without using include_str
-//    let input = format!("./inputs/unit_test/day{:02}.txt", day);
-    let input = format!("./inputs/day{:02}.txt", day);
-    // rust, read a file as a vector of strings
-    let lines = lines_from_file(input);
-    println!("Part 1: {}", solver.solve_part_1(lines.clone()));
-    println!("Part 2: {}", solver.solve_part_2(lines));
-}
-
-fn get_solver(day: u8) -> Option<Box<dyn solver::Solver>> {
-    match day {
-        1 => Some(Box::new(day01::Day1Solver {})),
-        2 => Some(Box::new(day02::Day2Solver {})),
-        3 => Some(Box::new(day03::Day3Solver {})),
-        4 => Some(Box::new(day04::Day4Solver {})),
-        5 => Some(Box::new(day05::Day5Solver {})),
-        6 => Some(Box::new(day06::Day6Solver {})),
-        7 => Some(Box::new(day07::Day7Solver {})),
-        8 => Some(Box::new(day08::Day8Solver {})),
-        9 => Some(Box::new(day09::Day9Solver {})),
-        _ => None,
-    }
-}
-
-/// Returns a vector of String. The idea is to use this format to consume lines
-/// from the files. We can also mock this out by passing Vec<String> to the solutions
-/// since they expect this format as well.
-pub fn lines_from_file(filename: impl AsRef<Path>) -> Vec<String> {
-    let file = File::open(filename).expect("no such file");
-    let buf = BufReader::new(file);
-    buf.lines()
-        .map(|l| l.expect("Could not parse line"))
-        .collect()
+
+use clap::Parser;
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use rust_aoc2022::allocations::{self, Allocations};
+use rust_aoc2022::answer::Answer;
+use rust_aoc2022::answers::{self, Status};
+use rust_aoc2022::bench::{self, BenchOptions, Stats};
+use rust_aoc2022::compare::{self, CompareOptions};
+use rust_aoc2022::examples;
+use rust_aoc2022::inputs::{self, Inputs};
+use rust_aoc2022::logging;
+use rust_aoc2022::output::{self, ErrorKind, Format, Record};
+use rust_aoc2022::repl::{self, Repl};
+use rust_aoc2022::runner::{self, RunOptions};
+use rust_aoc2022::solver::{DynSolver, Part};
+use rust_aoc2022::visualize::{FrameLog, Terminal};
+use rust_aoc2022::{get_solver, input_from_file, input_from_reader, SOLVERS, VISUALIZERS};
+
+use crate::cli::{Cli, Command};
+
+mod cli;
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: allocations::CountingAllocator = allocations::CountingAllocator;
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let cli = Cli::parse();
+    logging::init(cli.log.as_deref())?;
+    let inputs = Inputs::new(cli.inputs);
+    match cli.command {
+        Command::Run { day, part, input, example, visualize, fps, frames, format, allocations } => {
+            // A day without a solver has no input either, and saying so is more useful.
+            let solver = get_solver(day).ok_or_else(|| eyre!("there is no solver for day {}", day))?;
+            let (path, input) = read_input(&inputs, day, input, example)?;
+            if visualize {
+                play(day, part, &input, fps, frames)?;
+            }
+            match format {
+                Format::Text => run(solver.as_ref(), day, part, &input, allocations),
+                _ => run_records(solver.as_ref(), day, part, &path, &input, format),
+            }
+        }
+        Command::Bench { day, part, implementation, iterations, warmup, input, example } => {
+            let options = BenchOptions { iterations: iterations as usize, warmup: warmup as usize };
+            bench(&inputs, day, part, implementation, options, input, example)
+        }
+        Command::RunAll { jobs, timeout, format } => {
+            let jobs = match jobs {
+                Some(jobs) => jobs as usize,
+                None => thread::available_parallelism().map_or(1, usize::from),
+            };
+            run_all(&inputs, RunOptions { jobs, timeout: Duration::from_secs(timeout) }, format)
+        }
+        Command::List => {
+            list();
+            Ok(())
+        }
+        Command::Repl { day, input, example } => repl(&inputs, day, input, example),
+        Command::Compare { day, seeds, size } => compare(&inputs, day, CompareOptions { seeds, size }),
+        Command::Generate { day, size, seed, output } => generate(day, size, seed, output),
+        Command::Verify { answers, examples } => verify(&inputs, answers, examples),
+    }
+}
+
+fn run(solver: &dyn DynSolver, day: u8, part: Option<Part>, input: &str, report_allocations: bool) -> Result<()> {
+    if report_allocations && !allocations::is_counting() {
+        return Err(eyre!("allocations aren't counted, build with --features count-allocations"));
+    }
+    let (parsed, parse_allocations) = allocations::measure(|| solver.parse(input));
+    let parsed = parsed.wrap_err_with(|| format!("could not parse the input for day {}", day))?;
+    let mut part_allocations = vec![];
+    for part in parts(part) {
+        let (answer, allocations) = allocations::measure(|| solver.solve(part, parsed.as_ref()));
+        let answer = answer.wrap_err_with(|| format!("day {} part {} failed", day, part))?;
+        print_answer(part, &answer);
+        part_allocations.extend(allocations.map(|a| (part, a)));
+    }
+    if let Some(parse) = parse_allocations.filter(|_| report_allocations) {
+        println!("Allocations:");
+        println!("  parse  {}", parse);
+        for (part, allocations) in part_allocations {
+            println!("  part{}  {}", part, allocations);
+        }
+    }
+    Ok(())
+}
+
+/// run_records runs the given parts of a day and prints a record of each, in JSON. Unlike `run`,
+/// a failing part doesn't stop the others, and is reported in its record. With `jsonl`, each
+/// record is printed as soon as its part is done.
+fn run_records(
+    solver: &dyn DynSolver,
+    day: u8,
+    part: Option<Part>,
+    path: &Path,
+    input: &str,
+    format: Format,
+) -> Result<()> {
+    let start = Instant::now();
+    let parsed = solver.parse(input);
+    let parse_time = start.elapsed();
+    let mut records = vec![];
+    for part in parts(part) {
+        let record = match &parsed {
+            Err(e) => {
+                let message = format!("could not parse the input for day {}: {:#}", day, e);
+                Record { parse_time, ..Record::failed(day, part, path, ErrorKind::Error, message) }
+            }
+            Ok(parsed) => {
+                let start = Instant::now();
+                match solver.solve(part, parsed.as_ref()) {
+                    Ok(answer) => Record::solved(day, part, path, answer, parse_time, start.elapsed()),
+                    Err(e) => Record {
+                        parse_time,
+                        solve_time: start.elapsed(),
+                        ..Record::failed(day, part, path, ErrorKind::Error, format!("{:#}", e))
+                    },
+                }
+            }
+        };
+        if format == Format::Jsonl {
+            output::write_jsonl(io::stdout().lock(), &record)?;
+        }
+        records.push(record);
+    }
+    if format == Format::Json {
+        output::write_json(io::stdout().lock(), &records)?;
+    }
+
+    let failures = records.iter().filter(|r| r.error.is_some()).count();
+    if failures > 0 {
+        return Err(eyre!("{} of {} parts of day {} failed", failures, records.len(), day));
+    }
+    Ok(())
+}
+
+/// play shows the visualization of the given parts of a day, or writes its frames to a file.
+fn play(day: u8, part: Option<Part>, input: &str, fps: u32, frames: Option<PathBuf>) -> Result<()> {
+    let visualizer = VISUALIZERS.iter().find(|v| v.day == day).ok_or_else(|| {
+        let days: Vec<String> = VISUALIZERS.iter().map(|v| v.day.to_string()).collect();
+        eyre!("day {} can't be visualized, only days {} can", day, days.join(", "))
+    })?;
+    let visualizer = (visualizer.new)();
+    match frames {
+        Some(path) => {
+            let file = fs::File::create(&path).wrap_err_with(|| format!("could not create {}", path.display()))?;
+            let mut log = FrameLog::new(io::BufWriter::new(file));
+            for part in parts(part) {
+                visualizer.visualize(part, input, &mut |frame| log.write(&frame))?;
+            }
+            let written = log.finish()?;
+            eprintln!("wrote {} frames to {}", written, path.display());
+        }
+        None => {
+            let mut terminal = Terminal::new(io::stdout().lock(), fps);
+            for part in parts(part) {
+                visualizer.visualize(part, input, &mut |frame| terminal.show(&frame))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn bench(
+    inputs: &Inputs,
+    day: u8,
+    part: Option<Part>,
+    implementation: Option<String>,
+    options: BenchOptions,
+    input: Option<PathBuf>,
+    example: bool,
+) -> Result<()> {
+    let registration = SOLVERS
+        .iter()
+        .find(|r| r.day == day)
+        .ok_or_else(|| eyre!("there is no solver for day {}", day))?;
+    let implementations = match implementation {
+        Some(name) => vec![registration.implementation(&name).ok_or_else(|| {
+            let names: Vec<_> = registration.implementations().iter().map(|i| i.name).collect();
+            eyre!("day {} has no implementation {:?}, expected one of {}", day, name, names.join(", "))
+        })?],
+        None => registration.implementations(),
+    };
+    let (_, input) = read_input(inputs, day, input, example)?;
+    let parts = parts(part);
+
+    print!(
+        "{:<10}  {:<5}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "Impl", "Phase", "Min", "Median", "Mean", "Stddev"
+    );
+    // Allocations are only counted when the binary is built with the count-allocations feature.
+    if allocations::is_counting() {
+        print!("  {:>8}  {:>10}  {:>10}", "Allocs", "Bytes", "Peak");
+    }
+    println!();
+    for implementation in implementations {
+        let timings = bench::bench(implementation.solver.as_ref(), &input, &parts, options)
+            .wrap_err_with(|| format!("could not bench day {} ({})", day, implementation.name))?;
+        let profile = timings.allocations.as_ref();
+        print_stats(implementation.name, "parse", &timings.parse, profile.map(|p| &p.parse));
+        for (i, (part, stats)) in timings.parts.iter().enumerate() {
+            let allocations = profile.map(|p| &p.parts[i].1);
+            print_stats(implementation.name, &format!("part{}", part), stats, allocations);
+        }
+    }
+    Ok(())
+}
+
+fn print_stats(implementation: &str, phase: &str, stats: &Stats, allocations: Option<&Allocations>) {
+    print!(
+        "{:<10}  {:<5}  {:>10}  {:>10}  {:>10}  {:>10}",
+        implementation,
+        phase,
+        format!("{:.2?}", stats.min),
+        format!("{:.2?}", stats.median),
+        format!("{:.2?}", stats.mean),
+        format!("{:.2?}", stats.stddev),
+    );
+    if let Some(allocations) = allocations {
+        print!(
+            "  {:>8}  {:>10}  {:>10}",
+            allocations.count,
+            allocations::format_bytes(allocations.bytes),
+            allocations::format_bytes(allocations.peak),
+        );
+    }
+    println!();
+}
+
+fn run_all(inputs: &Inputs, options: RunOptions, format: Format) -> Result<()> {
+    // JSONL records are printed as each part finishes, rather than once every day is done. The
+    // first error writing them is kept, and the rest of the records aren't written.
+    let mut streamed: Result<()> = Ok(());
+    let report = runner::run_all(SOLVERS, inputs, options, |outcome| {
+        if format == Format::Jsonl && streamed.is_ok() {
+            streamed = output::write_jsonl(io::stdout().lock(), &Record::from(outcome));
+        }
+    });
+    streamed?;
+    if format != Format::Text {
+        if format == Format::Json {
+            let records: Vec<Record> = report.outcomes.iter().map(Record::from).collect();
+            output::write_json(io::stdout().lock(), &records)?;
+        }
+        if report.failures() > 0 {
+            return Err(eyre!("{} of {} runs failed", report.failures(), report.outcomes.len()));
+        }
+        return Ok(());
+    }
+
+    println!("{}", table_row(["Day", "Part", "Status", "Parse", "Solve"], "Answer"));
+    for outcome in &report.outcomes {
+        let (status, answer) = match &outcome.status {
+            runner::Status::Solved(answer) => ("ok", answer.to_string()),
+            runner::Status::Error(e) => ("ERROR", e.clone()),
+            runner::Status::Panicked(message) => ("PANICKED", message.clone()),
+            runner::Status::TimedOut => ("TIMED OUT", format!("gave up after {:?}", options.timeout)),
+        };
+        let (day, part) = (outcome.day.to_string(), outcome.part.to_string());
+        let (parse, solve) = (format!("{:.2?}", outcome.parse_time), format!("{:.2?}", outcome.solve_time));
+        println!("{}", table_row([&day, &part, status, &parse, &solve], &answer));
+    }
+    let solver_time: Duration = report.outcomes.iter().map(|o| o.solve_time).sum::<Duration>()
+        + report.outcomes.iter().filter(|o| o.part == Part::One).map(|o| o.parse_time).sum::<Duration>();
+    println!(
+        "Total: {} runs, {} failed, {:.2?} wall time, {:.2?} spent solving",
+        report.outcomes.len(),
+        report.failures(),
+        report.wall_time,
+        solver_time,
+    );
+
+    if report.failures() > 0 {
+        return Err(eyre!("{} of {} runs failed", report.failures(), report.outcomes.len()));
+    }
+    Ok(())
+}
+
+/// The widths of the columns of the run-all table that come before the answer.
+const TABLE_COLUMNS: [usize; 5] = [3, 4, 9, 10, 10];
+
+/// table_row formats a row of the run-all table. The status, the third column, is aligned to
+/// the left and the others to the right. Answers that span several lines, like bitmaps, have
+/// every line after the first indented to start under the answer column.
+fn table_row(columns: [&str; 5], answer: &str) -> String {
+    let mut row = String::new();
+    for (idx, (column, width)) in columns.into_iter().zip(TABLE_COLUMNS).enumerate() {
+        row += &match idx {
+            2 => format!("{:<width$}  ", column),
+            _ => format!("{:>width$}  ", column),
+        };
+    }
+    // Durations can have a µ in them, so the indent is counted in characters rather than bytes.
+    let indent = format!("\n{:1$}", "", row.chars().count());
+    row + &answer.replace('\n', &indent)
+}
+
+/// read_input reads the puzzle input from the given path, from stdin if the path is `-`, or
+/// from the day's example or real input file if no path is given. It returns the path that was
+/// read along with the input.
+fn read_input(inputs: &Inputs, day: u8, input: Option<PathBuf>, example: bool) -> Result<(PathBuf, String)> {
+    let path = match input {
+        Some(path) if path.as_os_str() == "-" => return Ok((path, input_from_reader(io::stdin().lock())?)),
+        Some(path) => path,
+        None if example => inputs.find(inputs::example_input(day))?,
+        None => inputs.find(inputs::day_input(day))?,
+    };
+    let input = input_from_file(&path)?;
+    Ok((path, input))
+}
+
+/// parts returns the part that was asked for, or both parts if none was.
+fn parts(part: Option<Part>) -> Vec<Part> {
+    match part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    }
+}
+
+/// print_answer prints an answer on the same line as its part, except for bitmaps, which start
+/// on a line of their own so the picture lines up.
+fn print_answer(part: Part, answer: &Answer) {
+    match answer {
+        Answer::Bitmap(_) => println!("Part {}:\n{}", part, answer),
+        _ => println!("Part {}: {}", part, answer),
+    }
+}
+
+fn verify(inputs: &Inputs, answers: Option<PathBuf>, examples: bool) -> Result<()> {
+    let verifications = if examples {
+        answers::check(SOLVERS, &examples::discover(inputs)?, inputs)
+    } else {
+        let answers = match answers {
+            Some(answers) => answers,
+            None => inputs.find("answers.json")?,
+        };
+        answers::verify(SOLVERS, &answers::load_answers(answers)?, inputs)
+    };
+
+    println!("{:>3}  {:>4}  {:<26}  {:<7}  {:>10}  {:>10}", "Day", "Part", "Input", "Status", "Parse", "Solve");
+    let mut failures = 0;
+    for v in &verifications {
+        let status = match &v.status {
+            Status::Pass => "pass",
+            Status::Fail { .. } => "FAIL",
+            Status::Missing(_) => "missing",
+            Status::Error(_) => "ERROR",
+        };
+        println!(
+            "{:>3}  {:>4}  {:<26}  {:<7}  {:>10}  {:>10}",
+            v.day,
+            v.part,
+            v.input.display(),
+            status,
+            format!("{:.2?}", v.parse_time),
+            format!("{:.2?}", v.solve_time),
+        );
+        match &v.status {
+            Status::Fail { expected, actual } => {
+                println!("     expected: {}", expected.to_string().replace('\n', "\n               "));
+                println!("     actual:   {}", actual.to_string().replace('\n', "\n               "));
+            }
+            Status::Missing(actual) => {
+                println!("     actual:   {}", actual.to_string().replace('\n', "\n               "));
+            }
+            Status::Error(e) => println!("     error:    {}", e),
+            Status::Pass => {}
+        }
+        if v.status.is_failure() {
+            failures += 1;
+        }
+    }
+
+    if failures > 0 {
+        return Err(eyre!("{} of {} checks failed", failures, verifications.len()));
+    }
+    Ok(())
+}
+
+fn compare(inputs: &Inputs, day: Option<u8>, options: CompareOptions) -> Result<()> {
+    let registrations: Vec<_> = match day {
+        Some(day) => vec![SOLVERS
+            .iter()
+            .find(|r| r.day == day)
+            .ok_or_else(|| eyre!("there is no solver for day {}", day))?],
+        None => SOLVERS.iter().filter(|r| r.implementations().len() > 1).collect(),
+    };
+    let mut disagreements = 0;
+    for registration in registrations {
+        let report = compare::compare(registration, inputs, options)?;
+        println!(
+            "Day {}: {} on {} inputs, {}",
+            registration.day,
+            report.implementations.join(", "),
+            report.cases,
+            match report.disagreements.len() {
+                0 => "no disagreements".to_string(),
+                1 => "1 disagreement".to_string(),
+                n => format!("{} disagreements", n),
+            }
+        );
+        for d in &report.disagreements {
+            println!("  part {} disagrees on {}, shrunk to {} bytes:", d.part, d.source, d.input.len());
+            for line in d.input.lines() {
+                println!("    | {}", line);
+            }
+            for (name, outcome) in &d.outcomes {
+                println!("    {:<10} {}", name, outcome.to_string().replace('\n', "\n               "));
+            }
+        }
+        disagreements += report.disagreements.len();
+    }
+
+    if disagreements > 0 {
+        return Err(eyre!("the implementations disagree on {} parts", disagreements));
+    }
+    Ok(())
+}
+
+fn generate(day: u8, size: usize, seed: Option<u64>, output: Option<PathBuf>) -> Result<()> {
+    let registration = SOLVERS
+        .iter()
+        .find(|r| r.day == day)
+        .ok_or_else(|| eyre!("there is no solver for day {}", day))?;
+    let seed = match seed {
+        Some(seed) => seed,
+        None => {
+            let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64);
+            eprintln!("seed: {}", seed);
+            seed
+        }
+    };
+    let input = registration.generate(seed, size);
+    match output {
+        Some(path) => fs::write(&path, input).wrap_err_with(|| format!("could not write {}", path.display())),
+        None => Ok(io::stdout().lock().write_all(input.as_bytes())?),
+    }
+}
+
+fn repl(inputs: &Inputs, day: u8, input: Option<PathBuf>, example: bool) -> Result<()> {
+    if input.as_ref().is_some_and(|path| path.as_os_str() == "-") {
+        return Err(eyre!("the REPL reads its commands from stdin, so the input has to come from a file"));
+    }
+    let (path, input) = read_input(inputs, day, input, example)?;
+    let mut repl = Repl::new(day, &input, Some(path))?;
+    repl::run(&mut repl, io::stdin().lock(), io::stdout().lock())
+}
+
+fn list() {
+    for registration in SOLVERS {
+        println!("{:>2}  {}::{}", registration.day, registration.module, registration.name);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_table_row_lines_up_bitmaps() {
+        let header = table_row(["Day", "Part", "Status", "Parse", "Solve"], "Answer");
+        let row = table_row(["10", "2", "ok", "12.34µs", "1.50ms"], "#..#\n.##.\n#..#");
+        let lines: Vec<&str> = row.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let column = |line: &str, answer: &str| line.chars().count() - answer.chars().count();
+        assert_eq!(column(&header, "Answer"), column(lines[0], "#..#"));
+        assert_eq!(column(lines[1], ".##."), column(lines[0], "#..#"));
+        assert_eq!(lines[2], format!("{:1$}#..#", "", column(lines[0], "#..#")));
+        assert_eq!(header, "Day  Part  Status          Parse       Solve  Answer");
+    }
 }
@@ -0,0 +1,291 @@
+//! Machine readable results, for dashboards and scripts that would otherwise scrape the
+//! `Part 1: ...` lines.
+//!
+//! Every part that is run becomes one `Record`. With `--format json` the records are printed as
+//! a single JSON array once every part has run, and with `--format jsonl` as one JSON object per
+//! line, as soon as each part is done. With `run-all`, that means JSONL records come in the order
+//! the parts finish in, rather than by day and part. The schema is the same in both:
+//!
+//! ```json
+//! {
+//!   "day": 10,
+//!   "part": 1,
+//!   "answer": 13140,
+//!   "input": "inputs/day10.txt",
+//!   "parse_time_ns": 41000,
+//!   "solve_time_ns": 12000,
+//!   "error": null
+//! }
+//! ```
+//!
+//! Every field is always there. `answer` is null when the part failed, in which case `error`
+//! holds `{"kind": ..., "message": ...}`, where the kind is one of `error`, `panicked` or
+//! `timed_out`. Answers are serialized as in the answers file, so bitmaps are arrays of rows.
+//! Fields may be added, but never renamed or removed.
+
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use color_eyre::eyre::Result;
+use serde::{Serialize, Serializer};
+
+use crate::answer::Answer;
+use crate::runner::{self, Outcome};
+use crate::solver::Part;
+
+/// Format is how results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Aligned text, for people.
+    #[default]
+    Text,
+    /// A JSON array of records.
+    Json,
+    /// One JSON record per line.
+    Jsonl,
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Text => f.pad("text"),
+            Format::Json => f.pad("json"),
+            Format::Jsonl => f.pad("jsonl"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "jsonl" => Ok(Format::Jsonl),
+            _ => Err(format!("expected text, json or jsonl, got {s:?}")),
+        }
+    }
+}
+
+/// Record is the result of running one part of one day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Option<Answer>,
+    /// The input file, or `-` for stdin.
+    pub input: String,
+    #[serde(rename = "parse_time_ns", serialize_with = "nanoseconds")]
+    pub parse_time: Duration,
+    #[serde(rename = "solve_time_ns", serialize_with = "nanoseconds")]
+    pub solve_time: Duration,
+    pub error: Option<ErrorDetails>,
+}
+
+/// ErrorDetails says why a part has no answer.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorDetails {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The input could not be read or parsed, or the solver returned an error.
+    Error,
+    Panicked,
+    TimedOut,
+}
+
+impl Record {
+    /// solved is the record of a part that has an answer.
+    pub fn solved(day: u8, part: Part, input: &Path, answer: Answer, parse_time: Duration, solve_time: Duration) -> Self {
+        Record { day, part, answer: Some(answer), input: input.display().to_string(), parse_time, solve_time, error: None }
+    }
+
+    /// failed is the record of a part that has no answer.
+    pub fn failed(day: u8, part: Part, input: &Path, kind: ErrorKind, message: impl Into<String>) -> Self {
+        Record {
+            day,
+            part,
+            answer: None,
+            input: input.display().to_string(),
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+            error: Some(ErrorDetails { kind, message: message.into() }),
+        }
+    }
+}
+
+impl From<&Outcome> for Record {
+    fn from(outcome: &Outcome) -> Self {
+        let (answer, error) = match &outcome.status {
+            runner::Status::Solved(answer) => (Some(answer.clone()), None),
+            runner::Status::Error(e) => (None, Some((ErrorKind::Error, e.clone()))),
+            runner::Status::Panicked(message) => (None, Some((ErrorKind::Panicked, message.clone()))),
+            runner::Status::TimedOut => (None, Some((ErrorKind::TimedOut, "the day ran past the timeout".to_string()))),
+        };
+        Record {
+            day: outcome.day,
+            part: outcome.part,
+            answer,
+            input: outcome.input.display().to_string(),
+            parse_time: outcome.parse_time,
+            solve_time: outcome.solve_time,
+            error: error.map(|(kind, message)| ErrorDetails { kind, message }),
+        }
+    }
+}
+
+fn nanoseconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    // A u64 of nanoseconds lasts for centuries, which is long enough for any solver.
+    serializer.serialize_u64(duration.as_nanos().try_into().unwrap_or(u64::MAX))
+}
+
+/// write_json writes the records as a single JSON array, followed by a newline.
+pub fn write_json(mut out: impl Write, records: &[Record]) -> Result<()> {
+    serde_json::to_writer_pretty(&mut out, records)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// write_jsonl writes a record as one line of JSON, and flushes it so that whoever is reading
+/// sees it straight away.
+pub fn write_jsonl(mut out: impl Write, record: &Record) -> Result<()> {
+    serde_json::to_writer(&mut out, record)?;
+    writeln!(out)?;
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        let input = Path::new("inputs/day10.txt");
+        vec![
+            Record::solved(10, Part::One, input, 13140.into(), Duration::from_micros(41), Duration::from_nanos(12)),
+            Record::solved(
+                10,
+                Part::Two,
+                input,
+                Answer::bitmap_from_rows(&["#.", ".#"]).unwrap(),
+                Duration::from_micros(41),
+                Duration::from_millis(1),
+            ),
+            Record::failed(2, Part::One, Path::new("-"), ErrorKind::Error, "line 2: invalid round \"B Q\""),
+        ]
+    }
+
+    // The schema is what dashboards depend on, so it is spelled out in full.
+    #[test]
+    fn test_schema() {
+        let json = serde_json::to_value(records()).unwrap();
+        assert_eq!(
+            json,
+            json!([
+                {
+                    "day": 10,
+                    "part": 1,
+                    "answer": 13140,
+                    "input": "inputs/day10.txt",
+                    "parse_time_ns": 41000,
+                    "solve_time_ns": 12,
+                    "error": null,
+                },
+                {
+                    "day": 10,
+                    "part": 2,
+                    "answer": ["#.", ".#"],
+                    "input": "inputs/day10.txt",
+                    "parse_time_ns": 41000,
+                    "solve_time_ns": 1000000,
+                    "error": null,
+                },
+                {
+                    "day": 2,
+                    "part": 1,
+                    "answer": null,
+                    "input": "-",
+                    "parse_time_ns": 0,
+                    "solve_time_ns": 0,
+                    "error": {"kind": "error", "message": "line 2: invalid round \"B Q\""},
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_records_from_outcomes() {
+        let outcome = |status| Outcome {
+            day: 9,
+            part: Part::Two,
+            input: PathBuf::from("day09.txt"),
+            status,
+            parse_time: Duration::from_nanos(5),
+            solve_time: Duration::from_nanos(7),
+        };
+        let json = serde_json::to_value(Record::from(&outcome(runner::Status::Solved(36.into())))).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "day": 9,
+                "part": 2,
+                "answer": 36,
+                "input": "day09.txt",
+                "parse_time_ns": 5,
+                "solve_time_ns": 7,
+                "error": null,
+            })
+        );
+        let errors = [
+            (runner::Status::Error("bad".to_string()), "error"),
+            (runner::Status::Panicked("oops".to_string()), "panicked"),
+            (runner::Status::TimedOut, "timed_out"),
+        ];
+        for (status, kind) in errors {
+            let json = serde_json::to_value(Record::from(&outcome(status))).unwrap();
+            assert_eq!(json["answer"], json!(null));
+            assert_eq!(json["error"]["kind"], json!(kind));
+        }
+    }
+
+    #[test]
+    fn test_write_json_and_jsonl() {
+        let records = records();
+        let mut out = vec![];
+        write_json(&mut out, &records).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed, serde_json::to_value(&records).unwrap());
+
+        let mut out = vec![];
+        for record in &records {
+            write_jsonl(&mut out, record).unwrap();
+        }
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), records.len());
+        for (line, record) in lines.iter().zip(&records) {
+            let parsed: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(parsed, serde_json::to_value(record).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_parse_format() {
+        for format in [Format::Text, Format::Json, Format::Jsonl] {
+            assert_eq!(format.to_string().parse::<Format>(), Ok(format));
+        }
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
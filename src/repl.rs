@@ -0,0 +1,321 @@
+//! An interactive prompt for poking at a day's parsed input while debugging.
+//!
+//! `repl <day>` loads the input once and then reads commands, one per line. Every day has the
+//! generic commands (`part1`, `part2`, `reload`, `time`, `help` and `quit`), and a day that
+//! implements `Explore` adds commands of its own, like `size <dir>` in day 7 or `step` in day 10.
+
+use std::any::Any;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::solver::{DynSolver, Part, Solver};
+
+/// Usage describes a command for the help.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    pub name: &'static str,
+    /// The arguments the command takes, such as `<row> <col>`.
+    pub args: &'static str,
+    pub help: &'static str,
+}
+
+/// Command is something a day lets you do with its state in the REPL. It is handed the words
+/// after its name, and returns what to print.
+pub struct Command<S> {
+    pub usage: Usage,
+    pub run: fn(&mut S, &[&str]) -> Result<String>,
+}
+
+impl<S> Command<S> {
+    pub fn new(name: &'static str, args: &'static str, help: &'static str, run: fn(&mut S, &[&str]) -> Result<String>) -> Self {
+        Command { usage: Usage { name, args, help }, run }
+    }
+}
+
+/// Explore adds a day's own commands to the REPL.
+pub trait Explore: Solver {
+    /// State is what the commands work on. It is made from the parsed input when the REPL
+    /// starts, and made again on every reload, so commands are free to change it.
+    type State;
+
+    fn explore(&self, input: &Self::Input) -> Result<Self::State>;
+    fn commands(&self) -> Vec<Command<Self::State>>;
+}
+
+/// Session is the object safe version of a day's state and its commands.
+pub trait Session {
+    fn usage(&self) -> Vec<Usage>;
+    /// call runs the named command, or returns `None` if the day has no such command.
+    fn call(&mut self, name: &str, args: &[&str]) -> Option<Result<String>>;
+}
+
+struct StateSession<S> {
+    state: S,
+    commands: Vec<Command<S>>,
+}
+
+impl<S> Session for StateSession<S> {
+    fn usage(&self) -> Vec<Usage> {
+        self.commands.iter().map(|c| c.usage).collect()
+    }
+
+    fn call(&mut self, name: &str, args: &[&str]) -> Option<Result<String>> {
+        let command = self.commands.iter().find(|c| c.usage.name == name)?;
+        Some((command.run)(&mut self.state, args))
+    }
+}
+
+/// DynExplore is the object safe version of `Explore`, which parses the input itself.
+pub trait DynExplore {
+    fn session(&self, input: &str) -> Result<Box<dyn Session>>;
+}
+
+impl<S> DynExplore for S
+where
+    S: Explore,
+    S::State: 'static,
+{
+    fn session(&self, input: &str) -> Result<Box<dyn Session>> {
+        let state = self.explore(&Solver::parse(self, input)?)?;
+        Ok(Box::new(StateSession { state, commands: self.commands() }))
+    }
+}
+
+/// Explorer ties a day to the solver that adds commands to its REPL. Explorers are created by
+/// `register_explorers!`.
+pub struct Explorer {
+    pub day: u8,
+    pub new: fn() -> Box<dyn DynExplore>,
+}
+
+/// register_explorers lists the solvers that implement `Explore` in `EXPLORERS`. The modules
+/// must already be declared by `register_solvers!`.
+#[macro_export]
+macro_rules! register_explorers {
+    ($($module:ident :: $solver:ident),* $(,)?) => {
+        /// Every solver that adds commands to the REPL, in the order they were registered.
+        pub const EXPLORERS: &[$crate::repl::Explorer] = &[
+            $(
+                $crate::repl::Explorer {
+                    day: $crate::solver::day_from_module(stringify!($module)),
+                    new: || -> Box<dyn $crate::repl::DynExplore> {
+                        Box::new(<$module::$solver as Default>::default())
+                    },
+                },
+            )*
+        ];
+    };
+}
+
+/// The commands every day has.
+const GENERIC_COMMANDS: [Usage; 6] = [
+    Usage { name: "part1", args: "", help: "solve part 1" },
+    Usage { name: "part2", args: "", help: "solve part 2" },
+    Usage { name: "reload", args: "", help: "read and parse the input again, starting over" },
+    Usage { name: "time", args: "<command>", help: "run a command and say how long it took" },
+    Usage { name: "help", args: "", help: "list the commands" },
+    Usage { name: "quit", args: "", help: "leave, as does end of input" },
+];
+
+/// Reply is what the REPL does after a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    Print(String),
+    Quit,
+}
+
+/// Repl holds a day's parsed input, and runs commands against it.
+pub struct Repl {
+    day: u8,
+    solver: Box<dyn DynSolver>,
+    explorer: Option<Box<dyn DynExplore>>,
+    /// Where the input was read from, if it can be read again.
+    source: Option<PathBuf>,
+    parsed: Box<dyn Any>,
+    session: Option<Box<dyn Session>>,
+}
+
+impl Repl {
+    /// new parses the input for the given day. `source` is the file it came from, which `reload`
+    /// reads again.
+    pub fn new(day: u8, input: &str, source: Option<PathBuf>) -> Result<Self> {
+        let solver = crate::get_solver(day).ok_or_else(|| eyre!("there is no solver for day {}", day))?;
+        let explorer = crate::EXPLORERS.iter().find(|e| e.day == day).map(|e| (e.new)());
+        let parsed = solver.parse(input).wrap_err_with(|| format!("could not parse the input for day {}", day))?;
+        let session = explorer.as_ref().map(|e| e.session(input)).transpose()?;
+        Ok(Repl { day, solver, explorer, source, parsed, session })
+    }
+
+    pub fn prompt(&self) -> String {
+        format!("day{:02}> ", self.day)
+    }
+
+    /// execute runs one line of input. A blank line does nothing.
+    pub fn execute(&mut self, line: &str) -> Result<Reply> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            return Ok(Reply::Print(String::new()));
+        };
+        let output = match name {
+            "part1" | "part2" => {
+                let part = if name == "part1" { Part::One } else { Part::Two };
+                self.solver.solve(part, self.parsed.as_ref())?.to_string()
+            }
+            "reload" => self.reload()?,
+            "time" => {
+                if args.is_empty() {
+                    return Err(eyre!("time needs a command to run, such as time part1"));
+                }
+                let start = Instant::now();
+                let reply = self.execute(&args.join(" "))?;
+                let took = format!("took {:.2?}", start.elapsed());
+                match reply {
+                    Reply::Print(output) if output.is_empty() => took,
+                    Reply::Print(output) => format!("{}\n{}", output, took),
+                    Reply::Quit => return Ok(Reply::Quit),
+                }
+            }
+            "help" => self.help(),
+            "quit" | "exit" => return Ok(Reply::Quit),
+            _ => match self.session.as_mut().and_then(|s| s.call(name, args)) {
+                Some(output) => output?,
+                None => return Err(eyre!("unknown command {:?}, try help", name)),
+            },
+        };
+        Ok(Reply::Print(output))
+    }
+
+    /// reload reads the input again and starts over from it. Nothing changes if it can't be
+    /// read or parsed.
+    fn reload(&mut self) -> Result<String> {
+        let source = self.source.as_ref().ok_or_else(|| eyre!("the input wasn't read from a file, so it can't be reloaded"))?;
+        let input = crate::input_from_file(source)?;
+        let parsed = self.solver.parse(&input)?;
+        let session = self.explorer.as_ref().map(|e| e.session(&input)).transpose()?;
+        self.parsed = parsed;
+        self.session = session;
+        Ok(format!("reloaded {}", source.display()))
+    }
+
+    fn help(&self) -> String {
+        let day_commands = self.session.as_ref().map(|s| s.usage()).unwrap_or_default();
+        let commands: Vec<Usage> = day_commands.into_iter().chain(GENERIC_COMMANDS).collect();
+        let width = commands.iter().map(|c| c.name.len() + c.args.len() + 1).max().unwrap_or(0);
+        commands
+            .iter()
+            .map(|c| format!("{:<width$}  {}", format!("{} {}", c.name, c.args).trim_end(), c.help, width = width))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// run reads commands from `input` until it ends or `quit` is given, printing a prompt before
+/// each and the reply after. Errors are printed and don't end the session.
+pub fn run(repl: &mut Repl, input: impl BufRead, mut out: impl Write) -> Result<()> {
+    let mut lines = input.lines();
+    loop {
+        write!(out, "{}", repl.prompt())?;
+        out.flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            writeln!(out)?;
+            return Ok(());
+        };
+        match repl.execute(&line) {
+            Ok(Reply::Print(output)) if output.is_empty() => {}
+            Ok(Reply::Print(output)) => writeln!(out, "{}", output)?,
+            Ok(Reply::Quit) => return Ok(()),
+            Err(e) => writeln!(out, "error: {:#}", e)?,
+        }
+    }
+}
+
+/// arg parses the argument at the given position, naming it in the error.
+pub fn arg<T: std::str::FromStr>(args: &[&str], idx: usize, name: &str) -> Result<T> {
+    let arg = args.get(idx).ok_or_else(|| eyre!("missing the {} argument", name))?;
+    arg.parse().map_err(|_| eyre!("invalid {} {:?}", name, arg))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::*;
+
+    use crate::inputs::{self, read_input};
+    use crate::{EXPLORERS, SOLVERS};
+
+    fn repl(day: u8) -> Repl {
+        Repl::new(day, &read_input(inputs::example_input(day)).unwrap(), None).unwrap()
+    }
+
+    fn print(repl: &mut Repl, line: &str) -> String {
+        match repl.execute(line).unwrap() {
+            Reply::Print(output) => output,
+            Reply::Quit => panic!("{line:?} quit"),
+        }
+    }
+
+    #[test]
+    fn test_every_explorer_is_a_registered_day() {
+        for explorer in EXPLORERS {
+            assert!(SOLVERS.iter().any(|r| r.day == explorer.day), "day {}", explorer.day);
+        }
+    }
+
+    #[test]
+    fn test_generic_commands() {
+        let mut repl = repl(1);
+        assert_eq!(print(&mut repl, "part1"), "24000");
+        assert_eq!(print(&mut repl, "  part2  "), "45000");
+        assert_eq!(print(&mut repl, ""), "");
+        assert!(print(&mut repl, "time part1").starts_with("24000\ntook "));
+        assert!(print(&mut repl, "help").contains("reload"));
+        assert_eq!(repl.execute("quit").unwrap(), Reply::Quit);
+        assert!(repl.execute("frobnicate").unwrap_err().to_string().contains("unknown command"));
+        assert!(repl.execute("time").is_err());
+        // The input came from a string, not a file.
+        assert!(repl.execute("reload").is_err());
+    }
+
+    #[test]
+    fn test_help_lists_the_day_commands_first() {
+        let help = print(&mut repl(7), "help");
+        assert!(help.starts_with("size <dir>"), "{help}");
+    }
+
+    #[test]
+    fn test_reload() {
+        let path = std::env::temp_dir().join(format!("aoc-repl-{}.txt", std::process::id()));
+        std::fs::write(&path, "1\n\n2\n").unwrap();
+        let mut repl = Repl::new(1, "1\n", Some(path.clone())).unwrap();
+        assert_eq!(print(&mut repl, "part1"), "1");
+        assert!(print(&mut repl, "reload").starts_with("reloaded"));
+        assert_eq!(print(&mut repl, "part2"), "3");
+        std::fs::write(&path, "x\n").unwrap();
+        assert!(repl.execute("reload").is_err());
+        assert_eq!(print(&mut repl, "part2"), "3");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_run() {
+        let mut repl = repl(2);
+        let mut out = vec![];
+        run(&mut repl, Cursor::new("part1\nnope\npart2\nquit\npart1\n"), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(
+            out,
+            "day02> 15\nday02> error: unknown command \"nope\", try help\nday02> 12\nday02> "
+        );
+    }
+
+    #[test]
+    fn test_arg() {
+        assert_eq!(arg::<usize>(&["3", "x"], 0, "row").unwrap(), 3);
+        assert_eq!(arg::<usize>(&["3", "x"], 1, "col").unwrap_err().to_string(), "invalid col \"x\"");
+        assert_eq!(arg::<usize>(&[], 0, "row").unwrap_err().to_string(), "missing the row argument");
+    }
+}
@@ -0,0 +1,489 @@
+use std::any::Any;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::answer::Answer;
+use crate::inputs::{self, Inputs};
+use crate::solver::{Part, Registration};
+
+/// RunOptions controls how `run_all` schedules the solvers.
+#[derive(Debug, Clone, Copy)]
+pub struct RunOptions {
+    /// How many solvers run at the same time.
+    pub jobs: usize,
+    /// How long a day may take, parsing included, before it is given up on.
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Solved(Answer),
+    /// The input could not be read or parsed, or the solver returned an error.
+    Error(String),
+    Panicked(String),
+    TimedOut,
+}
+
+impl Status {
+    pub fn is_failure(&self) -> bool {
+        !matches!(self, Status::Solved(_))
+    }
+}
+
+/// Outcome is the result of running one part of one day.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: Part,
+    /// The input file the day ran against, or would have if it could be found.
+    pub input: PathBuf,
+    pub status: Status,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// Report is every outcome of a run, ordered by day and part, and how long the run took.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub outcomes: Vec<Outcome>,
+    pub wall_time: Duration,
+}
+
+impl Report {
+    pub fn failures(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.status.is_failure()).count()
+    }
+}
+
+enum Message {
+    Started { job: usize, worker: usize, at: Instant },
+    Finished { job: usize, outcome: Outcome },
+    /// The job finished, either normally or by panicking. Parts that didn't report an outcome
+    /// are marked with the panic message, if there is one.
+    Done { job: usize, panic: Option<String> },
+}
+
+/// run_all runs both parts of every solver against its real input from `inputs`, on a pool of
+/// `options.jobs` threads. A solver that panics only fails its own day. A day that runs past the
+/// timeout is reported as timed out, and its thread is abandoned and replaced, since a thread
+/// can't be stopped from the outside. An abandoned thread takes no more jobs once it is unstuck,
+/// so no more than `options.jobs` solvers that haven't timed out run at once.
+///
+/// `on_outcome` is called with each outcome as soon as it is known, in the order they become
+/// known rather than by day and part.
+pub fn run_all(
+    solvers: &'static [Registration],
+    inputs: &Inputs,
+    options: RunOptions,
+    mut on_outcome: impl FnMut(&Outcome),
+) -> Report {
+    let start = Instant::now();
+    let (job_tx, job_rx) = mpsc::channel();
+    for job in 0..solvers.len() {
+        job_tx.send(job).expect("the job queue is still open");
+    }
+    drop(job_tx);
+    let job_rx = Arc::new(Mutex::new(job_rx));
+    let (msg_tx, msg_rx) = mpsc::channel();
+    // Each worker has a flag that is set when it is abandoned, indexed by the worker's number.
+    let mut cancelled: Vec<Arc<AtomicBool>> = vec![];
+    let spawn_worker = |cancelled: &mut Vec<Arc<AtomicBool>>| {
+        let worker_cancelled = Arc::new(AtomicBool::new(false));
+        cancelled.push(Arc::clone(&worker_cancelled));
+        let id = cancelled.len() - 1;
+        let jobs = Arc::clone(&job_rx);
+        let messages = msg_tx.clone();
+        let inputs = inputs.clone();
+        thread::spawn(move || worker(id, solvers, &inputs, &jobs, &messages, &worker_cancelled));
+    };
+    for _ in 0..options.jobs.clamp(1, solvers.len().max(1)) {
+        spawn_worker(&mut cancelled);
+    }
+
+    let mut outcomes: HashMap<(usize, Part), Outcome> = HashMap::new();
+    // The jobs that are running, with when they started and the worker running them.
+    let mut running: HashMap<usize, (Instant, usize)> = HashMap::new();
+    let mut finished = 0;
+    while finished < solvers.len() {
+        let deadline = running.values().map(|&(at, _)| at + options.timeout).min();
+        let message = match deadline {
+            Some(deadline) => msg_rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => msg_rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match message {
+            Ok(Message::Started { job, worker, at }) => {
+                running.insert(job, (at, worker));
+            }
+            Ok(Message::Finished { job, outcome }) => {
+                if running.contains_key(&job) {
+                    on_outcome(&outcome);
+                    outcomes.insert((job, outcome.part), outcome);
+                }
+            }
+            Ok(Message::Done { job, panic }) => {
+                if running.remove(&job).is_some() {
+                    finished += 1;
+                    let status = panic.map_or_else(
+                        || Status::Error("the solver stopped without an answer".to_string()),
+                        Status::Panicked,
+                    );
+                    fill_missing(&mut outcomes, job, solvers[job].day, inputs, status, &mut on_outcome);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let now = Instant::now();
+                let timed_out: Vec<usize> = running
+                    .iter()
+                    .filter(|(_, &(at, _))| at + options.timeout <= now)
+                    .map(|(&job, _)| job)
+                    .collect();
+                for job in timed_out {
+                    let (_, worker) = running.remove(&job).expect("the job is running");
+                    cancelled[worker].store(true, Ordering::Relaxed);
+                    finished += 1;
+                    fill_missing(&mut outcomes, job, solvers[job].day, inputs, Status::TimedOut, &mut on_outcome);
+                    spawn_worker(&mut cancelled);
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => unreachable!("run_all holds a sender"),
+        }
+    }
+
+    let mut outcomes: Vec<((usize, Part), Outcome)> = outcomes.into_iter().collect();
+    outcomes.sort_by_key(|(key, _)| *key);
+    Report { outcomes: outcomes.into_iter().map(|(_, o)| o).collect(), wall_time: start.elapsed() }
+}
+
+/// fill_missing gives every part of a job that has no outcome yet the given status, and passes
+/// each new outcome to `on_outcome`.
+fn fill_missing(
+    outcomes: &mut HashMap<(usize, Part), Outcome>,
+    job: usize,
+    day: u8,
+    inputs: &Inputs,
+    status: Status,
+    on_outcome: &mut impl FnMut(&Outcome),
+) {
+    let input = input_path(inputs, day);
+    for part in Part::ALL {
+        if let Entry::Vacant(entry) = outcomes.entry((job, part)) {
+            on_outcome(entry.insert(Outcome {
+                day,
+                part,
+                input: input.clone(),
+                status: status.clone(),
+                parse_time: Duration::ZERO,
+                solve_time: Duration::ZERO,
+            }));
+        }
+    }
+}
+
+/// worker runs jobs until there are none left, or until it is cancelled because its last job
+/// ran past the timeout and another worker has taken its place.
+fn worker(
+    id: usize,
+    solvers: &[Registration],
+    inputs: &Inputs,
+    jobs: &Mutex<Receiver<usize>>,
+    messages: &Sender<Message>,
+    cancelled: &AtomicBool,
+) {
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return;
+        }
+        // The lock is released as soon as a job is taken, so the other workers aren't blocked
+        // while this one runs.
+        let job = jobs.lock().map(|jobs| jobs.recv());
+        let Ok(Ok(job)) = job else {
+            return;
+        };
+        let registration = &solvers[job];
+        if messages.send(Message::Started { job, worker: id, at: Instant::now() }).is_err() {
+            return;
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run_day(job, registration, inputs, messages)));
+        let panic = result.err().map(|payload| panic_message(payload.as_ref()));
+        if messages.send(Message::Done { job, panic }).is_err() {
+            return;
+        }
+    }
+}
+
+/// run_day parses the input once and solves both parts from it, reporting each part as soon as
+/// it is solved.
+fn run_day(job: usize, registration: &Registration, inputs: &Inputs, messages: &Sender<Message>) {
+    let input = input_path(inputs, registration.day);
+    let outcome = |part, status, parse_time, solve_time| {
+        let outcome = Outcome { day: registration.day, part, input: input.clone(), status, parse_time, solve_time };
+        Message::Finished { job, outcome }
+    };
+    let solver = registration.solver();
+    let start = Instant::now();
+    let parsed = inputs.read(inputs::day_input(registration.day)).and_then(|input| solver.parse(&input));
+    let parse_time = start.elapsed();
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            for part in Part::ALL {
+                let _ = messages.send(outcome(part, Status::Error(format!("{:#}", e)), parse_time, Duration::ZERO));
+            }
+            return;
+        }
+    };
+    for part in Part::ALL {
+        let start = Instant::now();
+        let status = match solver.solve(part, parsed.as_ref()) {
+            Ok(answer) => Status::Solved(answer),
+            Err(e) => Status::Error(format!("{:#}", e)),
+        };
+        let _ = messages.send(outcome(part, status, parse_time, start.elapsed()));
+    }
+}
+
+/// input_path is where a day's real input is, or just its name if it can't be found.
+fn input_path(inputs: &Inputs, day: u8) -> PathBuf {
+    inputs.find(inputs::day_input(day)).unwrap_or_else(|_| inputs::day_input(day))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "the solver panicked".to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::AtomicUsize;
+
+    use color_eyre::eyre::Result;
+
+    use super::*;
+
+    use crate::solver::{DynSolver, Solver};
+
+    #[derive(Default)]
+    struct PanickingSolver;
+
+    impl Solver for PanickingSolver {
+        type Input = ();
+
+        fn parse(&self, _: &str) -> Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part1(&self, _: &Self::Input) -> Result<Answer> {
+            Ok(1.into())
+        }
+
+        fn part2(&self, _: &Self::Input) -> Result<Answer> {
+            panic!("part 2 is broken")
+        }
+    }
+
+    #[derive(Default)]
+    struct SlowSolver;
+
+    impl Solver for SlowSolver {
+        type Input = ();
+
+        fn parse(&self, _: &str) -> Result<Self::Input> {
+            thread::sleep(Duration::from_secs(10));
+            Ok(())
+        }
+
+        fn part1(&self, _: &Self::Input) -> Result<Answer> {
+            Ok(1.into())
+        }
+
+        fn part2(&self, _: &Self::Input) -> Result<Answer> {
+            Ok(2.into())
+        }
+    }
+
+    static TEST_SOLVERS: [Registration; 3] = [
+        Registration {
+            day: 1,
+            module: "day01",
+            name: "PanickingSolver",
+            new: || -> Box<dyn DynSolver> { Box::new(PanickingSolver) },
+            alternatives: Vec::new,
+            generator: |_, _| String::new(),
+        },
+        Registration {
+            day: 2,
+            module: "day02",
+            name: "SlowSolver",
+            new: || -> Box<dyn DynSolver> { Box::new(SlowSolver) },
+            alternatives: Vec::new,
+            generator: |_, _| String::new(),
+        },
+        Registration {
+            day: 3,
+            module: "day03",
+            name: "MissingInput",
+            new: || -> Box<dyn DynSolver> { Box::new(PanickingSolver) },
+            alternatives: Vec::new,
+            generator: |_, _| String::new(),
+        },
+    ];
+
+    /// The threads that ran `StuckSolver` and `CountingSolver`, and how many counting solvers
+    /// ran at once, at the most and right now.
+    static STUCK_THREADS: Mutex<Vec<thread::ThreadId>> = Mutex::new(Vec::new());
+    static COUNTING_THREADS: Mutex<Vec<thread::ThreadId>> = Mutex::new(Vec::new());
+    static COUNTING_NOW: AtomicUsize = AtomicUsize::new(0);
+    static COUNTING_MAX: AtomicUsize = AtomicUsize::new(0);
+
+    /// StuckSolver runs past the timeout, but finishes while there are still jobs to take.
+    #[derive(Default)]
+    struct StuckSolver;
+
+    impl Solver for StuckSolver {
+        type Input = ();
+
+        fn parse(&self, _: &str) -> Result<Self::Input> {
+            STUCK_THREADS.lock().unwrap().push(thread::current().id());
+            thread::sleep(Duration::from_millis(300));
+            Ok(())
+        }
+
+        fn part1(&self, _: &Self::Input) -> Result<Answer> {
+            Ok(1.into())
+        }
+
+        fn part2(&self, _: &Self::Input) -> Result<Answer> {
+            Ok(2.into())
+        }
+    }
+
+    /// CountingSolver takes a while to parse, and records the thread it runs on.
+    #[derive(Default)]
+    struct CountingSolver;
+
+    impl Solver for CountingSolver {
+        type Input = ();
+
+        fn parse(&self, _: &str) -> Result<Self::Input> {
+            COUNTING_THREADS.lock().unwrap().push(thread::current().id());
+            let now = COUNTING_NOW.fetch_add(1, Ordering::SeqCst) + 1;
+            COUNTING_MAX.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(60));
+            COUNTING_NOW.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn part1(&self, _: &Self::Input) -> Result<Answer> {
+            Ok(1.into())
+        }
+
+        fn part2(&self, _: &Self::Input) -> Result<Answer> {
+            Ok(2.into())
+        }
+    }
+
+    const fn counting(day: u8) -> Registration {
+        Registration {
+            day,
+            module: "counting",
+            name: "CountingSolver",
+            new: || -> Box<dyn DynSolver> { Box::new(CountingSolver) },
+            alternatives: Vec::new,
+            generator: |_, _| String::new(),
+        }
+    }
+
+    static STUCK_SOLVERS: [Registration; 6] = [
+        Registration {
+            day: 1,
+            module: "stuck",
+            name: "StuckSolver",
+            new: || -> Box<dyn DynSolver> { Box::new(StuckSolver) },
+            alternatives: Vec::new,
+            generator: |_, _| String::new(),
+        },
+        counting(2),
+        counting(3),
+        counting(4),
+        counting(5),
+        counting(6),
+    ];
+
+    #[test]
+    fn test_timed_out_workers_take_no_more_jobs() {
+        let dir = std::env::temp_dir().join(format!("aoc-runner-stuck-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for day in 1..=6 {
+            std::fs::write(dir.join(format!("day{:02}.txt", day)), "").unwrap();
+        }
+
+        // The stuck day times out after 150ms and wakes up at 300ms, while the counting days run
+        // one after the other from 150ms to 450ms.
+        let options = RunOptions { jobs: 1, timeout: Duration::from_millis(150) };
+        let report = run_all(&STUCK_SOLVERS, &Inputs::with_roots(vec![dir.clone()]), options, |_| {});
+        assert_eq!(report.outcomes[0].status, Status::TimedOut);
+        assert_eq!(report.failures(), 2);
+
+        let stuck = STUCK_THREADS.lock().unwrap().clone();
+        let counting = COUNTING_THREADS.lock().unwrap().clone();
+        assert_eq!((stuck.len(), counting.len()), (1, 5));
+        assert!(!counting.contains(&stuck[0]), "the timed out worker took another job");
+        assert_eq!(COUNTING_MAX.load(Ordering::SeqCst), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_all_solves_every_day() {
+        let options = RunOptions { jobs: 4, timeout: Duration::from_secs(60) };
+        let report = run_all(crate::SOLVERS, &Inputs::new(None), options, |_| {});
+        assert_eq!(report.outcomes.len(), crate::SOLVERS.len() * Part::ALL.len());
+        assert_eq!(report.failures(), 0);
+        let days: Vec<u8> = report.outcomes.iter().map(|o| o.day).collect();
+        assert!(days.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_run_all_isolates_panics_timeouts_and_errors() {
+        let dir = std::env::temp_dir().join(format!("aoc-runner-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day01.txt"), "").unwrap();
+        std::fs::write(dir.join("day02.txt"), "").unwrap();
+
+        let options = RunOptions { jobs: 1, timeout: Duration::from_millis(200) };
+        let start = Instant::now();
+        let mut streamed = vec![];
+        let report = run_all(&TEST_SOLVERS, &Inputs::with_roots(vec![dir.clone()]), options, |outcome| {
+            streamed.push((outcome.day, outcome.part, start.elapsed()))
+        });
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // Every outcome is passed on once, and day 1's are passed on before day 2 times out.
+        assert_eq!(streamed.len(), report.outcomes.len());
+        let mut days: Vec<(u8, Part)> = streamed.iter().map(|&(day, part, _)| (day, part)).collect();
+        days.sort();
+        assert_eq!(days, report.outcomes.iter().map(|o| (o.day, o.part)).collect::<Vec<_>>());
+        assert!(streamed.iter().filter(|&&(day, _, _)| day == 1).all(|&(_, _, at)| at < options.timeout));
+
+        let statuses: Vec<(u8, Part, &Status)> = report.outcomes.iter().map(|o| (o.day, o.part, &o.status)).collect();
+        assert_eq!(statuses[0], (1, Part::One, &Status::Solved(1.into())));
+        assert_eq!(statuses[1], (1, Part::Two, &Status::Panicked("part 2 is broken".to_string())));
+        assert_eq!(statuses[2], (2, Part::One, &Status::TimedOut));
+        assert_eq!(statuses[3], (2, Part::Two, &Status::TimedOut));
+        assert!(matches!(statuses[4], (3, Part::One, Status::Error(_))));
+        assert!(matches!(statuses[5], (3, Part::Two, Status::Error(_))));
+        assert_eq!(report.failures(), 5);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,4 +1,206 @@
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Result};
+use serde::{Serialize, Serializer};
+
+use crate::answer::Answer;
+use crate::utils::rng::Rng;
+
+/// Solver solves both parts of a day. The raw puzzle input is parsed once into `Input`, and
+/// both parts then work from the parsed model.
 pub trait Solver {
-    fn solve_part_1(&self, lines: Vec<String>) -> String;
-    fn solve_part_2(&self, lines: Vec<String>) -> String;
+    type Input;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+    fn part1(&self, input: &Self::Input) -> Result<Answer>;
+    fn part2(&self, input: &Self::Input) -> Result<Answer>;
+
+    /// solve dispatches to the solution for the given part.
+    fn solve(&self, part: Part, input: &Self::Input) -> Result<Answer> {
+        match part {
+            Part::One => self.part1(input),
+            Part::Two => self.part2(input),
+        }
+    }
+
+    /// alternatives returns other implementations of the same day, keyed by a short name. They
+    /// must produce the same answers as the default one, which `compare::compare` checks, and
+    /// exist so they can be benchmarked against each other.
+    fn alternatives() -> Vec<(&'static str, Self)>
+    where
+        Self: Sized,
+    {
+        vec![]
+    }
+}
+
+/// DynSolver is the object safe version of `Solver` that the registry hands out. The parsed
+/// input is passed around as `Any`, and each solver downcasts it back to its own `Input`.
+pub trait DynSolver {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>>;
+    fn solve(&self, part: Part, input: &dyn Any) -> Result<Answer>;
+
+    /// run parses the input and solves the given part in one go.
+    fn run(&self, part: Part, input: &str) -> Result<Answer> {
+        self.solve(part, self.parse(input)?.as_ref())
+    }
+}
+
+impl<S> DynSolver for S
+where
+    S: Solver,
+    S::Input: 'static,
+{
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        Ok(Box::new(Solver::parse(self, input)?))
+    }
+
+    fn solve(&self, part: Part, input: &dyn Any) -> Result<Answer> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .ok_or_else(|| eyre!("the input was parsed by a different solver"))?;
+        Solver::solve(self, part, input)
+    }
+}
+
+/// Part is one of the two puzzles released every day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+/// Parts serialize to their number.
+impl Serialize for Part {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Part::One => serializer.serialize_u8(1),
+            Part::Two => serializer.serialize_u8(2),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("expected part 1 or 2, got {s:?}")),
+        }
+    }
+}
+
+/// Registration ties a day of the calendar to the solver that lives in its `dayNN` module.
+/// Registrations are created by `register_solvers!`, never by hand.
+pub struct Registration {
+    pub day: u8,
+    pub module: &'static str,
+    pub name: &'static str,
+    pub new: fn() -> Box<dyn DynSolver>,
+    pub alternatives: fn() -> Vec<Implementation>,
+    pub generator: fn(&mut Rng, usize) -> String,
+}
+
+/// The name the default implementation of every day is registered under.
+pub const DEFAULT_IMPLEMENTATION: &str = "default";
+
+/// Implementation is one of the ways a day can be solved.
+pub struct Implementation {
+    pub name: &'static str,
+    pub solver: Box<dyn DynSolver>,
+}
+
+impl Registration {
+    pub fn solver(&self) -> Box<dyn DynSolver> {
+        (self.new)()
+    }
+
+    /// generate returns a random input of the given size for this day, see `Generator`.
+    pub fn generate(&self, seed: u64, size: usize) -> String {
+        (self.generator)(&mut Rng::new(seed), size)
+    }
+
+    /// implementations returns the default implementation followed by any alternatives.
+    pub fn implementations(&self) -> Vec<Implementation> {
+        let default = Implementation { name: DEFAULT_IMPLEMENTATION, solver: self.solver() };
+        std::iter::once(default).chain((self.alternatives)()).collect()
+    }
+
+    /// implementation returns the implementation with the given name, if there is one.
+    pub fn implementation(&self, name: &str) -> Option<Implementation> {
+        self.implementations().into_iter().find(|i| i.name == name)
+    }
+}
+
+/// day_from_module turns a module name of the form `dayNN` into NN. It runs at compile time,
+/// so a module that doesn't follow the naming scheme fails the build.
+pub const fn day_from_module(module: &str) -> u8 {
+    let bytes = module.as_bytes();
+    assert!(
+        bytes.len() == 5 && bytes[0] == b'd' && bytes[1] == b'a' && bytes[2] == b'y',
+        "solver modules must be named dayNN"
+    );
+    assert!(bytes[3].is_ascii_digit() && bytes[4].is_ascii_digit(), "solver modules must be named dayNN");
+    (bytes[3] - b'0') * 10 + (bytes[4] - b'0')
+}
+
+/// register_solvers declares each `dayNN` module and registers its solver in `SOLVERS`, so a
+/// day can't be added to the crate without also being registered. Every solver must implement
+/// `Default` and `Generator`.
+///
+/// ```ignore
+/// register_solvers! {
+///     day01::Day1Solver,
+///     day02::Day2Solver,
+/// }
+/// ```
+#[macro_export]
+macro_rules! register_solvers {
+    ($($module:ident :: $solver:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        /// Every registered solver, in the order they were registered.
+        pub const SOLVERS: &[$crate::solver::Registration] = &[
+            $(
+                $crate::solver::Registration {
+                    day: {
+                        const DAY: u8 = $crate::solver::day_from_module(stringify!($module));
+                        DAY
+                    },
+                    module: stringify!($module),
+                    name: stringify!($solver),
+                    new: || -> Box<dyn $crate::solver::DynSolver> {
+                        Box::new(<$module::$solver as Default>::default())
+                    },
+                    alternatives: || {
+                        <$module::$solver as $crate::solver::Solver>::alternatives()
+                            .into_iter()
+                            .map(|(name, solver)| $crate::solver::Implementation { name, solver: Box::new(solver) })
+                            .collect()
+                    },
+                    generator: |rng, size| {
+                        let solver = <$module::$solver as Default>::default();
+                        $crate::generate::Generator::generate(&solver, rng, size)
+                    },
+                },
+            )*
+        ];
+    };
 }
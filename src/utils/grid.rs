@@ -1,12 +1,33 @@
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+use std::iter::FusedIterator;
 use std::ops::{Add, Sub};
+use std::str::FromStr;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+use color_eyre::eyre::{eyre, Report, Result};
+
+/// GridCoord is a position on a grid, or a step between two positions. `x` is the column,
+/// growing to the right, and `y` is the row, growing downwards. Code that thinks in rows and
+/// columns should use `from_row_col`, `row` and `col` rather than mapping them to x and y itself.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct GridCoord {
     pub x: isize,
     pub y: isize,
 }
 
+impl GridCoord {
+    pub const fn from_row_col(row: isize, col: isize) -> Self {
+        Self { x: col, y: row }
+    }
+
+    pub const fn row(&self) -> isize {
+        self.y
+    }
+
+    pub const fn col(&self) -> isize {
+        self.x
+    }
+}
+
 impl Add for GridCoord {
     type Output = Self;
 
@@ -29,18 +50,117 @@ impl Sub for GridCoord {
     }
 }
 
-impl From<(isize, isize)> for GridCoord {
-    fn from((x, y): (isize, isize)) -> Self {
-        Self { x, y }
-    }
-}
-
 impl Debug for GridCoord {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "({}, {})", self.x, self.y)
     }
 }
 
+/// Direction is one of the eight ways to step from a cell to a touching one. Up is towards row 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// The directions along rows and columns, clockwise from up.
+    pub const CARDINAL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    /// offset is the step from a cell to the one next to it in this direction.
+    pub const fn offset(&self) -> GridCoord {
+        match self {
+            Direction::Up => GridCoord::from_row_col(-1, 0),
+            Direction::UpRight => GridCoord::from_row_col(-1, 1),
+            Direction::Right => GridCoord::from_row_col(0, 1),
+            Direction::DownRight => GridCoord::from_row_col(1, 1),
+            Direction::Down => GridCoord::from_row_col(1, 0),
+            Direction::DownLeft => GridCoord::from_row_col(1, -1),
+            Direction::Left => GridCoord::from_row_col(0, -1),
+            Direction::UpLeft => GridCoord::from_row_col(-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        !Direction::CARDINAL.contains(self)
+    }
+
+    /// turn_right returns the direction a quarter turn clockwise from this one.
+    pub fn turn_right(&self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// turn_left returns the direction a quarter turn anticlockwise from this one.
+    pub fn turn_left(&self) -> Direction {
+        self.rotate(6)
+    }
+
+    /// opposite returns the direction that undoes a step in this one.
+    pub fn opposite(&self) -> Direction {
+        self.rotate(4)
+    }
+
+    /// rotate turns clockwise by eighths of a turn.
+    fn rotate(&self, eighths: usize) -> Direction {
+        Direction::ALL[(*self as usize + eighths) % Direction::ALL.len()]
+    }
+
+    /// from_char returns the direction written as a letter (`U`, `D`, `L` or `R`), a compass
+    /// point (`N`, `E`, `S` or `W`) or an arrow (`^`, `v`, `<`, `>`, or one of `↑↗→↘↓↙←↖`).
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            'U' | 'N' | '^' | '↑' => Some(Direction::Up),
+            '↗' => Some(Direction::UpRight),
+            'R' | 'E' | '>' | '→' => Some(Direction::Right),
+            '↘' => Some(Direction::DownRight),
+            'D' | 'S' | 'v' | '↓' => Some(Direction::Down),
+            '↙' => Some(Direction::DownLeft),
+            'L' | 'W' | '<' | '←' => Some(Direction::Left),
+            '↖' => Some(Direction::UpLeft),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = Report;
+
+    /// Directions are parsed as with `from_char`, and diagonals can also be written as two
+    /// compass points, like `NE`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let direction = match (chars.next(), chars.next(), chars.next()) {
+            (Some(c), None, _) => Direction::from_char(c),
+            (Some('N'), Some('E'), None) => Some(Direction::UpRight),
+            (Some('S'), Some('E'), None) => Some(Direction::DownRight),
+            (Some('S'), Some('W'), None) => Some(Direction::DownLeft),
+            (Some('N'), Some('W'), None) => Some(Direction::UpLeft),
+            _ => None,
+        };
+        direction.ok_or_else(|| eyre!("expected a direction like U, N, NE or ^, found {:?}", s))
+    }
+}
+
+/// Grid is a rectangle of cells, stored row by row.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Grid<T> where T: Default + Clone {
     width: usize,
     height: usize,
@@ -48,6 +168,8 @@ pub struct Grid<T> where T: Default + Clone {
 }
 
 impl<T> Grid<T> where T: Default + Clone {
+    /// new returns a grid `width` columns wide and `height` rows high, with every cell set to
+    /// the default.
     pub fn new(width: usize, height: usize) -> Self {
         Self {
             width,
@@ -57,7 +179,7 @@ impl<T> Grid<T> where T: Default + Clone {
     }
 
     pub fn in_bounds(&self, p: GridCoord) -> bool {
-        (p.x as usize) < self.width && (p.y as usize) < self.height
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
     }
 
     /// cell_mut returns a mutable reference to the contents at the given location.
@@ -95,13 +217,446 @@ impl<T> Grid<T> where T: Default + Clone {
         Some((p.y * (self.width as isize) + p.x) as usize)
     }
 
-    pub fn grid_coordinates(&self) -> Vec<GridCoord> {
-        let mut coords = vec![];
-        for row in 0..self.height {
-            for col in 0..self.width {
-                coords.push((row as isize, col as isize).into())
+    /// from_lines builds a grid with a row for each line, and a cell for each character, made
+    /// by `cell`. Every line must have as many characters as the first. Errors give the row and
+    /// column, counted from 1, of the offending cell or line.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a str>, mut cell: impl FnMut(char) -> Result<T>) -> Result<Self> {
+        let mut data = vec![];
+        let (mut width, mut height) = (0, 0);
+        for (row, line) in lines.into_iter().enumerate() {
+            let start = data.len();
+            for (col, c) in line.chars().enumerate() {
+                data.push(cell(c).map_err(|e| eyre!("row {}, column {}: {:#}", row + 1, col + 1, e))?);
+            }
+            let cells = data.len() - start;
+            if row == 0 {
+                width = cells;
+            } else if cells != width {
+                return Err(eyre!("row {}: expected {} cells, found {}", row + 1, width, cells));
+            }
+            height += 1;
+        }
+        Ok(Self { width, height, data })
+    }
+
+    /// render_with draws the grid as text, with a line for each row, using `cell` to draw each
+    /// cell. It is the opposite of `from_lines`.
+    pub fn render_with(&self, mut cell: impl FnMut(GridCoord, &T) -> char) -> String {
+        let mut drawing = String::with_capacity((self.width + 1) * self.height);
+        for (idx, value) in self.data.iter().enumerate() {
+            let coord = GridCoord::from_row_col((idx / self.width) as isize, (idx % self.width) as isize);
+            drawing.push(cell(coord, value));
+            if (idx + 1) % self.width == 0 {
+                drawing.push('\n');
             }
         }
-        coords
+        drawing
+    }
+
+    /// coordinates returns every coordinate of the grid in the order the cells are stored: the
+    /// top row from left to right, then the row below it, and so on.
+    pub fn coordinates(&self) -> impl Iterator<Item = GridCoord> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |idx| GridCoord::from_row_col((idx / width) as isize, (idx % width) as isize))
+    }
+
+    /// neighbors4 returns the cells above, left of, right of and below `p`, in that order,
+    /// leaving out those that are off the grid.
+    pub fn neighbors4(&self, p: GridCoord) -> impl Iterator<Item = GridCoord> + '_ {
+        NEIGHBORS4.iter().map(move |&step| p + step).filter(move |&n| self.in_bounds(n))
+    }
+
+    /// neighbors8 returns the cells around `p`, diagonals included, row by row, leaving out
+    /// those that are off the grid.
+    pub fn neighbors8(&self, p: GridCoord) -> impl Iterator<Item = GridCoord> + '_ {
+        NEIGHBORS8.iter().map(move |&step| p + step).filter(move |&n| self.in_bounds(n))
+    }
+
+    /// ray returns the cells met by walking from `from` in steps of `step`, up to the edge of
+    /// the grid. `from` itself isn't part of the ray. A ray from off the grid, or with a zero
+    /// step, is empty.
+    pub fn ray(&self, from: GridCoord, step: GridCoord) -> Line {
+        let len = if !self.in_bounds(from) || step == GridCoord::default() {
+            0
+        } else {
+            // How many steps fit along each axis before leaving the grid. An axis that isn't
+            // moved along never ends the ray.
+            let fits = |pos: isize, step: isize, size: usize| match step.signum() {
+                1 => (size as isize - 1 - pos) / step,
+                -1 => pos / -step,
+                _ => isize::MAX,
+            };
+            fits(from.col(), step.col(), self.width).min(fits(from.row(), step.row(), self.height)) as usize
+        };
+        Line { next: from + step, step, len }
+    }
+
+    /// row returns the cells of a row from left to right, or nothing if there is no such row.
+    /// Use `rev` to go from right to left.
+    pub fn row(&self, row: usize) -> Line {
+        let len = if row < self.height { self.width } else { 0 };
+        Line { next: GridCoord::from_row_col(row as isize, 0), step: GridCoord::from_row_col(0, 1), len }
+    }
+
+    /// column returns the cells of a column from top to bottom, or nothing if there is no such
+    /// column. Use `rev` to go from the bottom to the top.
+    pub fn column(&self, col: usize) -> Line {
+        let len = if col < self.width { self.height } else { 0 };
+        Line { next: GridCoord::from_row_col(0, col as isize), step: GridCoord::from_row_col(1, 0), len }
+    }
+
+    /// border returns the cells on the edge of the grid, each once, in the order they are
+    /// stored.
+    pub fn border(&self) -> impl Iterator<Item = GridCoord> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let top = (0..if height > 0 { width } else { 0 }).map(|col| GridCoord::from_row_col(0, col));
+        // The rows in between only have their first and last cells on the edge, which are the
+        // same cell in a grid one column wide.
+        let sides = (1..height - 1).flat_map(move |row| {
+            [0, width - 1].into_iter().take(width.min(2) as usize).map(move |col| GridCoord::from_row_col(row, col))
+        });
+        let bottom = (0..if height > 1 { width } else { 0 }).map(move |col| GridCoord::from_row_col(height - 1, col));
+        top.chain(sides).chain(bottom)
+    }
+}
+
+pub(crate) const NEIGHBORS4: [GridCoord; 4] = [
+    GridCoord::from_row_col(-1, 0),
+    GridCoord::from_row_col(0, -1),
+    GridCoord::from_row_col(0, 1),
+    GridCoord::from_row_col(1, 0),
+];
+
+pub(crate) const NEIGHBORS8: [GridCoord; 8] = [
+    GridCoord::from_row_col(-1, -1),
+    GridCoord::from_row_col(-1, 0),
+    GridCoord::from_row_col(-1, 1),
+    GridCoord::from_row_col(0, -1),
+    GridCoord::from_row_col(0, 1),
+    GridCoord::from_row_col(1, -1),
+    GridCoord::from_row_col(1, 0),
+    GridCoord::from_row_col(1, 1),
+];
+
+/// Line is a run of evenly spaced coordinates on a grid, such as a row, a column or a ray. It is
+/// worked out as it is iterated, and can be iterated from either end.
+#[derive(Clone, Debug)]
+pub struct Line {
+    next: GridCoord,
+    step: GridCoord,
+    len: usize,
+}
+
+impl Iterator for Line {
+    type Item = GridCoord;
+
+    fn next(&mut self) -> Option<GridCoord> {
+        if self.len == 0 {
+            return None;
+        }
+        let coord = self.next;
+        self.next = self.next + self.step;
+        self.len -= 1;
+        Some(coord)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl DoubleEndedIterator for Line {
+    fn next_back(&mut self) -> Option<GridCoord> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let steps = self.len as isize;
+        Some(GridCoord::from_row_col(self.next.row() + steps * self.step.row(), self.next.col() + steps * self.step.col()))
+    }
+}
+
+impl ExactSizeIterator for Line {}
+
+impl FusedIterator for Line {}
+
+/// CellChar is a cell that is written as a single character, so that grids of it can be parsed
+/// with `FromStr` and drawn with `Display`.
+pub trait CellChar: Sized {
+    fn from_char(c: char) -> Result<Self>;
+    fn to_char(&self) -> char;
+}
+
+/// Any character is a cell of itself, for grids of arbitrary symbols.
+impl CellChar for char {
+    fn from_char(c: char) -> Result<Self> {
+        Ok(c)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+/// Booleans are written as `#` for true and `.` for false, like the pictures in the puzzles.
+impl CellChar for bool {
+    fn from_char(c: char) -> Result<Self> {
+        match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(eyre!("expected # or ., found {:?}", c)),
+        }
+    }
+
+    fn to_char(&self) -> char {
+        if *self { '#' } else { '.' }
+    }
+}
+
+/// Small numbers are written as a single digit.
+impl CellChar for u8 {
+    fn from_char(c: char) -> Result<Self> {
+        c.to_digit(10).map(|d| d as u8).ok_or_else(|| eyre!("expected a digit, found {:?}", c))
+    }
+
+    fn to_char(&self) -> char {
+        char::from_digit(u32::from(*self), 10).unwrap_or('?')
+    }
+}
+
+impl<T> FromStr for Grid<T> where T: CellChar + Default + Clone {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::from_lines(s.lines(), T::from_char)
+    }
+}
+
+impl<T> Display for Grid<T> where T: CellChar + Default + Clone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render_with(|_, cell| cell.to_char()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::*;
+
+    /// A grid 3 columns wide and 2 rows high, where each cell holds its row and column, like
+    ///
+    /// ```text
+    /// 00 01 02
+    /// 10 11 12
+    /// ```
+    fn grid() -> Grid<usize> {
+        let mut grid = Grid::new(3, 2);
+        for row in 0..2 {
+            for col in 0..3 {
+                *grid.cell_mut(GridCoord::from_row_col(row, col)).unwrap() = (row * 10 + col) as usize;
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn test_coordinates() {
+        let coord = GridCoord::from_row_col(1, 2);
+        assert_eq!(coord, GridCoord { x: 2, y: 1 });
+        assert_eq!((coord.row(), coord.col()), (1, 2));
+        assert_eq!(coord + GridCoord::from_row_col(1, -1), GridCoord::from_row_col(2, 1));
+        assert_eq!(coord - GridCoord::from_row_col(1, 2), GridCoord { x: 0, y: 0 });
+    }
+
+    #[test]
+    fn test_direction_offsets() {
+        assert_eq!(Direction::Up.offset(), GridCoord { x: 0, y: -1 });
+        assert_eq!(Direction::DownLeft.offset(), GridCoord::from_row_col(1, -1));
+        for direction in Direction::ALL {
+            let offset = direction.offset();
+            assert_eq!(offset + direction.opposite().offset(), GridCoord::default(), "{:?}", direction);
+            assert_eq!(direction.is_diagonal(), offset.row() != 0 && offset.col() != 0, "{:?}", direction);
+        }
+        assert_eq!(Direction::CARDINAL.iter().filter(|d| d.is_diagonal()).count(), 0);
+    }
+
+    #[test_case(Direction::Up, Direction::Right, Direction::Left)]
+    #[test_case(Direction::Left, Direction::Up, Direction::Down)]
+    #[test_case(Direction::UpRight, Direction::DownRight, Direction::UpLeft)]
+    fn test_direction_turns(direction: Direction, right: Direction, left: Direction) {
+        assert_eq!(direction.turn_right(), right);
+        assert_eq!(direction.turn_left(), left);
+        assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+        assert_eq!(direction.turn_left().turn_right(), direction);
+    }
+
+    #[test_case("U", Direction::Up)]
+    #[test_case("N", Direction::Up; "north")]
+    #[test_case("^", Direction::Up; "caret")]
+    #[test_case("v", Direction::Down)]
+    #[test_case("W", Direction::Left)]
+    #[test_case("→", Direction::Right)]
+    #[test_case("↙", Direction::DownLeft)]
+    #[test_case("NE", Direction::UpRight)]
+    #[test_case("SW", Direction::DownLeft; "two compass points")]
+    fn test_parse_direction(s: &str, expected: Direction) {
+        assert_eq!(s.parse::<Direction>().unwrap(), expected);
+    }
+
+    #[test_case("")]
+    #[test_case("X")]
+    #[test_case("UR")]
+    #[test_case("NEW")]
+    fn test_bad_directions(s: &str) {
+        let err = s.parse::<Direction>().unwrap_err();
+        assert!(err.to_string().starts_with("expected a direction"), "{err}");
+    }
+
+    #[test]
+    fn test_size() {
+        let grid = grid();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+    }
+
+    #[test]
+    fn test_in_bounds() {
+        let grid = grid();
+        assert!(grid.in_bounds(GridCoord::from_row_col(1, 2)));
+        assert!(!grid.in_bounds(GridCoord::from_row_col(2, 1)));
+        assert!(!grid.in_bounds(GridCoord::from_row_col(0, 3)));
+        assert!(!grid.in_bounds(GridCoord::from_row_col(-1, 0)));
+        assert!(!grid.in_bounds(GridCoord::from_row_col(0, -1)));
+    }
+
+    #[test]
+    fn test_cells() {
+        let mut grid = grid();
+        assert_eq!(grid.cell(GridCoord::from_row_col(0, 2)), Some(&2));
+        assert_eq!(grid.cell(GridCoord::from_row_col(1, 0)), Some(&10));
+        assert_eq!(grid.cell(GridCoord::from_row_col(2, 0)), None);
+        assert_eq!(grid.cell(GridCoord::from_row_col(0, 3)), None);
+        *grid.cell_mut(GridCoord::from_row_col(1, 2)).unwrap() = 99;
+        assert_eq!(grid.cell(GridCoord::from_row_col(1, 2)), Some(&99));
+        assert!(grid.cell_mut(GridCoord::from_row_col(0, -1)).is_none());
+    }
+
+    #[test]
+    fn test_coordinates_are_in_row_major_order() {
+        let grid = grid();
+        let coords: Vec<GridCoord> = grid.coordinates().collect();
+        let cells: Vec<usize> = coords.iter().map(|&c| *grid.cell(c).unwrap()).collect();
+        assert_eq!(cells, [0, 1, 2, 10, 11, 12]);
+        assert_eq!(coords[1], GridCoord::from_row_col(0, 1));
+        assert_eq!(Grid::<u8>::new(0, 0).coordinates().count(), 0);
+    }
+
+    fn coords(cells: &[(isize, isize)]) -> Vec<GridCoord> {
+        cells.iter().map(|&(row, col)| GridCoord::from_row_col(row, col)).collect()
+    }
+
+    #[test_case((0, 0), &[(0, 1), (1, 0)]; "corner")]
+    #[test_case((1, 1), &[(0, 1), (1, 0), (1, 2)]; "bottom edge")]
+    #[test_case((3, 0), &[]; "off the grid")]
+    fn test_neighbors4(p: (isize, isize), expected: &[(isize, isize)]) {
+        let p = GridCoord::from_row_col(p.0, p.1);
+        assert_eq!(grid().neighbors4(p).collect::<Vec<_>>(), coords(expected));
+    }
+
+    #[test_case((0, 0), &[(0, 1), (1, 0), (1, 1)]; "corner")]
+    #[test_case((1, 1), &[(0, 0), (0, 1), (0, 2), (1, 0), (1, 2)]; "bottom edge")]
+    fn test_neighbors8(p: (isize, isize), expected: &[(isize, isize)]) {
+        let p = GridCoord::from_row_col(p.0, p.1);
+        assert_eq!(grid().neighbors8(p).collect::<Vec<_>>(), coords(expected));
+    }
+
+    #[test_case((0, 0), (0, 1), &[(0, 1), (0, 2)]; "right")]
+    #[test_case((1, 2), (0, -1), &[(1, 1), (1, 0)]; "left")]
+    #[test_case((0, 0), (1, 1), &[(1, 1)]; "diagonal")]
+    #[test_case((0, 0), (0, 2), &[(0, 2)]; "long step")]
+    #[test_case((1, 0), (1, 0), &[]; "already at the edge")]
+    #[test_case((0, 0), (0, 0), &[]; "no step")]
+    #[test_case((-1, 0), (1, 0), &[]; "off the grid")]
+    fn test_ray(from: (isize, isize), step: (isize, isize), expected: &[(isize, isize)]) {
+        let ray = grid().ray(GridCoord::from_row_col(from.0, from.1), GridCoord::from_row_col(step.0, step.1));
+        assert_eq!(ray.len(), expected.len());
+        assert_eq!(ray.clone().collect::<Vec<_>>(), coords(expected));
+        assert_eq!(ray.rev().collect::<Vec<_>>(), coords(expected).into_iter().rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = grid();
+        assert_eq!(grid.row(1).collect::<Vec<_>>(), coords(&[(1, 0), (1, 1), (1, 2)]));
+        assert_eq!(grid.row(1).rev().collect::<Vec<_>>(), coords(&[(1, 2), (1, 1), (1, 0)]));
+        assert_eq!(grid.column(2).collect::<Vec<_>>(), coords(&[(0, 2), (1, 2)]));
+        assert_eq!(grid.column(2).rev().collect::<Vec<_>>(), coords(&[(1, 2), (0, 2)]));
+        assert_eq!(grid.row(2).count(), 0);
+        assert_eq!(grid.column(3).count(), 0);
+
+        let mut row = grid.row(0);
+        assert_eq!((row.next(), row.next_back()), (Some(GridCoord::from_row_col(0, 0)), Some(GridCoord::from_row_col(0, 2))));
+        assert_eq!(row.collect::<Vec<_>>(), coords(&[(0, 1)]));
+    }
+
+    #[test_case(3, 2, &[(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]; "no inside")]
+    #[test_case(3, 3, &[(0, 0), (0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1), (2, 2)]; "square")]
+    #[test_case(1, 3, &[(0, 0), (1, 0), (2, 0)]; "one column")]
+    #[test_case(2, 1, &[(0, 0), (0, 1)]; "one row")]
+    #[test_case(0, 0, &[]; "empty")]
+    fn test_border(width: usize, height: usize, expected: &[(isize, isize)]) {
+        assert_eq!(Grid::<u8>::new(width, height).border().collect::<Vec<_>>(), coords(expected));
+    }
+
+    #[test]
+    fn test_from_lines() {
+        let grid = Grid::from_lines(["ab", "cd", "ef"], |c| Ok(c as u32 - 'a' as u32)).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.cell(GridCoord::from_row_col(2, 1)), Some(&5));
+        assert_eq!(grid.cell(GridCoord::from_row_col(0, 1)), Some(&1));
+    }
+
+    #[test_case("12\n3x\n", "row 2, column 2: expected a digit, found 'x'"; "bad cell")]
+    #[test_case("123\n45\n", "row 2: expected 3 cells, found 2"; "short row")]
+    #[test_case("1\n23\n", "row 2: expected 1 cells, found 2"; "long row")]
+    fn test_from_lines_errors(input: &str, expected: &str) {
+        let err = input.parse::<Grid<u8>>().unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[test]
+    fn test_empty_grid() {
+        let grid: Grid<u8> = "".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.to_string(), "");
+    }
+
+    fn round_trip<T: CellChar + Default + Clone + Debug>(input: &str) -> Grid<T> {
+        let grid: Grid<T> = input.parse().unwrap();
+        assert_eq!(grid.to_string(), input);
+        grid
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let digits = round_trip::<u8>("30373\n25512\n65332\n");
+        assert_eq!(digits.cell(GridCoord::from_row_col(2, 0)), Some(&6));
+        let booleans = round_trip::<bool>("#..#\n.##.\n");
+        assert_eq!(booleans.cell(GridCoord::from_row_col(1, 1)), Some(&true));
+        let symbols = round_trip::<char>("S.#\n.<E\n~@.\n>v^\n");
+        assert_eq!((symbols.width(), symbols.height()), (3, 4));
+        assert_eq!(symbols.cell(GridCoord::from_row_col(1, 2)), Some(&'E'));
+    }
+
+    #[test]
+    fn test_render_with() {
+        let grid: Grid<u8> = "123\n456\n".parse().unwrap();
+        let drawing = grid.render_with(|coord, &n| match coord == GridCoord::from_row_col(1, 0) {
+            true => '*',
+            false if n % 2 == 0 => 'e',
+            false => 'o',
+        });
+        assert_eq!(drawing, "oeo\n*oe\n");
     }
 }
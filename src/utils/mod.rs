@@ -1 +1,5 @@
-pub mod grid;
\ No newline at end of file
+pub mod grid;
+pub mod parse;
+pub mod rng;
+pub mod search;
+pub mod sparse_grid;
@@ -0,0 +1,273 @@
+//! Parsers shared by the days, built on nom.
+//!
+//! The parsers here use `VerboseError`, so that the drivers (`parse_all` and `parse_lines`)
+//! can turn a failure into a `ParseError` that points at the offending line and column. Label
+//! parsers with `nom::error::context` to get messages like "expected a direction".
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take, take_till1, take_while};
+use nom::character::complete::{digit1, line_ending, one_of, satisfy};
+use nom::combinator::{all_consuming, cut, eof, map_parser, map_res, not, opt, peek, recognize};
+use nom::error::{context, ErrorKind, VerboseError, VerboseErrorKind};
+use nom::multi::{many0, many1, separated_list1};
+use nom::sequence::{pair, terminated};
+use nom::{Finish, IResult, Parser};
+
+/// PResult is the result of the parsers in this module, and of the parsers built from them.
+pub type PResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+/// unsigned parses a run of decimal digits into any unsigned integer type. Numbers that don't
+/// fit in the type are rejected.
+pub fn unsigned<T: FromStr>(i: &str) -> PResult<'_, T> {
+    context("an unsigned number", map_res(digit1, str::parse))(i)
+}
+
+/// signed parses a decimal number with an optional sign into any integer type.
+pub fn signed<T: FromStr>(i: &str) -> PResult<'_, T> {
+    context("a number", map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse))(i)
+}
+
+/// identifier parses a letter or underscore followed by any number of letters, digits and
+/// underscores.
+pub fn identifier(i: &str) -> PResult<'_, &str> {
+    context(
+        "an identifier",
+        recognize(pair(
+            satisfy(|c| c.is_alphabetic() || c == '_'),
+            take_while(|c: char| c.is_alphanumeric() || c == '_'),
+        )),
+    )(i)
+}
+
+/// word parses everything up to the next whitespace, such as a file name.
+pub fn word(i: &str) -> PResult<'_, &str> {
+    context("a word", take_till1(char::is_whitespace))(i)
+}
+
+/// column parses a cell that is exactly `width` characters wide. The cell must be consumed
+/// entirely by `parser`.
+pub fn column<'a, O>(
+    width: usize,
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, O> {
+    map_parser(take(width), all_consuming(parser))
+}
+
+/// columns parses a row of cells that are `width` characters wide, with `separator` between
+/// each of them.
+pub fn columns<'a, O>(
+    width: usize,
+    separator: &'static str,
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<O>> {
+    separated_list1(tag(separator), column(width, parser))
+}
+
+/// line_break parses the end of a line that is followed by another line with something on it.
+fn line_break(i: &str) -> PResult<'_, &str> {
+    terminated(line_ending, not(alt((line_ending, eof))))(i)
+}
+
+/// lines parses one or more lines, each of which must be consumed entirely by `parser`. A line
+/// that doesn't parse is an error, rather than the end of the list, so the error points at it.
+/// The list ends at the end of the input or at a blank line.
+pub fn lines<'a, O>(
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<O>> {
+    let line_end = context("the end of the line", peek(alt((line_ending, eof))));
+    separated_list1(line_break, cut(terminated(parser, line_end)))
+}
+
+/// sections parses one or more sections separated by blank lines, like the lists of calories
+/// in day 1.
+pub fn sections<'a, O>(
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<O>> {
+    separated_list1(pair(line_ending, many1(line_ending)), parser)
+}
+
+/// parse_all runs `parser` over the whole input. Anything but line endings left over after it
+/// is an error.
+pub fn parse_all<'a, O>(
+    input: &'a str,
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> Result<O, ParseError> {
+    all_consuming(terminated(parser, many0(line_ending)))(input)
+        .finish()
+        .map(|(_, output)| output)
+        .map_err(|e| ParseError::from_verbose(input, e))
+}
+
+/// parse_lines runs `parser` over every line of the input.
+pub fn parse_lines<'a, O>(
+    input: &'a str,
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> Result<Vec<O>, ParseError> {
+    parse_all(input, lines(parser))
+}
+
+/// ParseError is a parse failure with the position it happened at. It displays as the message
+/// followed by the offending line, with a caret under the column:
+///
+/// ```text
+/// line 2, column 1: expected a direction
+///   |
+/// 2 | X 2
+///   | ^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    line_text: String,
+    message: String,
+}
+
+impl ParseError {
+    /// new creates an error at the start of `at`, which must be a slice of `source`. Lines and
+    /// columns are counted from 1, and columns count characters rather than bytes.
+    pub fn new(source: &str, at: &str, message: impl Into<String>) -> Self {
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(source.as_ptr() as usize)
+            .filter(|&offset| offset <= source.len())
+            .unwrap_or(source.len());
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..].find('\n').map_or(source.len(), |i| offset + i);
+        ParseError {
+            line: source[..offset].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            line_text: source[line_start..line_end].trim_end_matches('\r').to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn from_verbose(source: &str, error: VerboseError<&str>) -> Self {
+        let Some(&(at, ref kind)) = error.errors.first() else {
+            return ParseError::new(source, "", "could not parse the input");
+        };
+        let label = error.errors.iter().find_map(|(_, kind)| match kind {
+            VerboseErrorKind::Context(label) => Some(*label),
+            _ => None,
+        });
+        let message = match (label, kind) {
+            (Some(label), VerboseErrorKind::Nom(ErrorKind::MapRes | ErrorKind::MapOpt | ErrorKind::Verify)) => {
+                // "an unsigned number" becomes "invalid unsigned number".
+                let label = label.strip_prefix("an ").or_else(|| label.strip_prefix("a ")).unwrap_or(label);
+                format!("invalid {}", label)
+            }
+            (Some(label), _) => format!("expected {}", label),
+            (None, VerboseErrorKind::Char(c)) => format!("expected {:?}", c),
+            (None, VerboseErrorKind::Nom(ErrorKind::Eof)) => "expected the end of the input".to_string(),
+            (None, _) => "unexpected input".to_string(),
+        };
+        ParseError::new(source, at, message)
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "line {}, column {}: {}", self.line, self.column, self.message)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.line_text)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(self.column - 1))
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod test {
+    use nom::character::complete::char;
+    use nom::sequence::separated_pair;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("42", 42)]
+    #[test_case("0", 0)]
+    #[test_case("255", 255)]
+    fn test_unsigned(input: &str, expected: u8) {
+        assert_eq!(parse_all(input, unsigned::<u8>).unwrap(), expected);
+    }
+
+    #[test_case("-3", -3; "negative")]
+    #[test_case("+3", 3; "explicitly positive")]
+    #[test_case("17", 17)]
+    fn test_signed(input: &str, expected: i32) {
+        assert_eq!(parse_all(input, signed::<i32>).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_numbers_out_of_range() {
+        let err = parse_all("256", unsigned::<u8>).unwrap_err();
+        assert_eq!(err.message(), "invalid unsigned number");
+        assert!(parse_all("-1", unsigned::<u8>).is_err());
+    }
+
+    #[test]
+    fn test_identifier_and_word() {
+        assert_eq!(parse_all("_foo42", identifier).unwrap(), "_foo42");
+        assert!(parse_all("42foo", identifier).is_err());
+        assert_eq!(parse_all("a.b/c", word).unwrap(), "a.b/c");
+    }
+
+    #[test]
+    fn test_columns() {
+        let cell = alt((tag("[A]"), tag("[B]"), tag("   ")));
+        assert_eq!(parse_all("[A]     [B]", columns(3, " ", cell)).unwrap(), vec!["[A]", "   ", "[B]"]);
+    }
+
+    #[test]
+    fn test_sections_of_lines() {
+        let input = "1\n2\n\n3\n\n\n4\n";
+        let parsed = parse_all(input, sections(lines(unsigned::<u32>))).unwrap();
+        assert_eq!(parsed, vec![vec![1, 2], vec![3], vec![4]]);
+    }
+
+    #[test]
+    fn test_parse_lines_accepts_crlf() {
+        assert_eq!(parse_lines("1\r\n2\r\n", unsigned::<u32>).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_error_points_at_the_bad_line() {
+        let pair = separated_pair(unsigned::<u32>, char('-'), unsigned::<u32>);
+        let err = parse_lines("1-2\n3-x\n5-6\n", pair).unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 3));
+        assert_eq!(err.to_string(), "line 2, column 3: expected an unsigned number\n  |\n2 | 3-x\n  |   ^");
+    }
+
+    #[test]
+    fn test_error_for_leftover_input() {
+        let err = parse_lines("1\n2 3\n", unsigned::<u32>).unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 2));
+        assert_eq!(err.message(), "expected the end of the line");
+
+        let err = parse_all("1\n\nfoo", lines(unsigned::<u32>)).unwrap_err();
+        assert_eq!((err.line(), err.column()), (3, 1));
+        assert_eq!(err.message(), "expected the end of the input");
+    }
+
+    #[test]
+    fn test_error_columns_count_characters() {
+        let err = ParseError::new("héllo", &"héllo"[3..], "oops");
+        assert_eq!((err.line(), err.column()), (1, 3));
+    }
+}
@@ -0,0 +1,339 @@
+//! Shortest paths over anything that implements `Graph`.
+//!
+//! `bfs` counts the edges of a path and ignores their costs, `dijkstra` adds up the costs, and
+//! `astar` does the same as `dijkstra` while exploring fewer nodes, given a heuristic that never
+//! overestimates the cost left to a goal. Grids are searched through `GridGraph`, which takes
+//! the cost of stepping between cells from a closure.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use crate::utils::grid::{Grid, GridCoord};
+
+/// Graph is a set of nodes joined by one-way edges, each with a cost.
+pub trait Graph {
+    type Node: Copy + Eq + Hash;
+
+    /// neighbors returns the nodes an edge leads to from `node`, with the cost of each edge.
+    fn neighbors(&self, node: Self::Node) -> impl Iterator<Item = (Self::Node, u64)>;
+}
+
+/// An adjacency list is a graph, where nodes that aren't keys have no edges.
+impl<N> Graph for HashMap<N, Vec<(N, u64)>> where N: Copy + Eq + Hash {
+    type Node = N;
+
+    fn neighbors(&self, node: N) -> impl Iterator<Item = (N, u64)> {
+        self.get(&node).into_iter().flatten().copied()
+    }
+}
+
+/// GridGraph searches a grid, stepping from each cell to the ones next to it. `cost` is given
+/// the cell stepped from and the cell stepped onto, and returns the cost of the step, or `None`
+/// if it can't be taken.
+pub struct GridGraph<'a, T, F> where T: Default + Clone {
+    grid: &'a Grid<T>,
+    cost: F,
+    diagonals: bool,
+}
+
+impl<'a, T, F> GridGraph<'a, T, F> where T: Default + Clone, F: Fn(&T, &T) -> Option<u64> {
+    pub fn new(grid: &'a Grid<T>, cost: F) -> Self {
+        Self { grid, cost, diagonals: false }
+    }
+
+    /// with_diagonals lets the search also step diagonally.
+    pub fn with_diagonals(mut self) -> Self {
+        self.diagonals = true;
+        self
+    }
+}
+
+impl<T, F> Graph for GridGraph<'_, T, F> where T: Default + Clone, F: Fn(&T, &T) -> Option<u64> {
+    type Node = GridCoord;
+
+    fn neighbors(&self, node: GridCoord) -> impl Iterator<Item = (GridCoord, u64)> {
+        let from = self.grid.cell(node);
+        let neighbors: Box<dyn Iterator<Item = GridCoord>> = match self.diagonals {
+            true => Box::new(self.grid.neighbors8(node)),
+            false => Box::new(self.grid.neighbors4(node)),
+        };
+        neighbors.filter_map(move |to| (self.cost)(from?, self.grid.cell(to)?).map(|cost| (to, cost)))
+    }
+}
+
+/// Search is the outcome of a search from a start node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Search<N> where N: Eq + Hash {
+    /// The cost of the path to the goal that was found, or `None` if no goal could be reached.
+    pub distance: Option<u64>,
+    /// The path from the start to the goal, both included, or nothing if no goal was reached.
+    pub path: Vec<N>,
+    /// Every node whose neighbors were explored.
+    pub visited: HashSet<N>,
+}
+
+impl<N> Search<N> where N: Eq + Hash {
+    /// goal returns the goal that was reached, if any.
+    pub fn goal(&self) -> Option<&N> {
+        self.path.last()
+    }
+}
+
+/// bfs finds the path from `start` to a node for which `is_goal` is true with the fewest edges,
+/// whatever they cost. The distance is the number of edges.
+pub fn bfs<G: Graph>(graph: &G, start: G::Node, mut is_goal: impl FnMut(G::Node) -> bool) -> Search<G::Node> {
+    let mut came_from = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([(start, 0)]);
+    let mut seen = HashSet::from([start]);
+    while let Some((node, distance)) = queue.pop_front() {
+        visited.insert(node);
+        if is_goal(node) {
+            return found(node, distance, &came_from, visited);
+        }
+        for (next, _) in graph.neighbors(node) {
+            if seen.insert(next) {
+                came_from.insert(next, node);
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    not_found(visited)
+}
+
+/// dijkstra finds the cheapest path from `start` to a node for which `is_goal` is true.
+pub fn dijkstra<G: Graph>(graph: &G, start: G::Node, is_goal: impl FnMut(G::Node) -> bool) -> Search<G::Node> {
+    astar(graph, start, is_goal, |_| 0)
+}
+
+/// astar finds the cheapest path from `start` to a node for which `is_goal` is true, exploring
+/// the nodes that `heuristic` says are closest to a goal first. The path is only the cheapest if
+/// the heuristic never returns more than the cheapest cost from a node to a goal. A node is
+/// explored again whenever a cheaper way to it is found, which only happens if the heuristic
+/// can drop by more than the cost of an edge.
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: impl FnMut(G::Node) -> bool,
+    mut heuristic: impl FnMut(G::Node) -> u64,
+) -> Search<G::Node> {
+    let mut came_from = HashMap::new();
+    let mut costs = HashMap::from([(start, 0)]);
+    let mut visited = HashSet::new();
+    let mut frontier = BinaryHeap::from([Frontier { estimate: heuristic(start), cost: 0, node: start }]);
+    while let Some(Frontier { cost, node, .. }) = frontier.pop() {
+        // A node is pushed again whenever a cheaper way to it is found, so any entry that is
+        // costlier than the cheapest way known is out of date.
+        if costs[&node] < cost {
+            continue;
+        }
+        visited.insert(node);
+        if is_goal(node) {
+            return found(node, cost, &came_from, visited);
+        }
+        for (next, step) in graph.neighbors(node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+            costs.insert(next, next_cost);
+            came_from.insert(next, node);
+            frontier.push(Frontier { estimate: next_cost + heuristic(next), cost: next_cost, node: next });
+        }
+    }
+    not_found(visited)
+}
+
+/// Frontier is a node waiting to be explored, which is ordered so that a `BinaryHeap` pops the
+/// lowest estimate first.
+struct Frontier<N> {
+    estimate: u64,
+    cost: u64,
+    node: N,
+}
+
+impl<N> Ord for Frontier<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Between equal estimates, the node furthest along is likely closer to the goal.
+        other.estimate.cmp(&self.estimate).then(self.cost.cmp(&other.cost))
+    }
+}
+
+impl<N> PartialOrd for Frontier<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> PartialEq for Frontier<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N> Eq for Frontier<N> {}
+
+fn found<N: Copy + Eq + Hash>(goal: N, distance: u64, came_from: &HashMap<N, N>, visited: HashSet<N>) -> Search<N> {
+    let mut path = vec![goal];
+    while let Some(&prev) = came_from.get(path.last().unwrap()) {
+        path.push(prev);
+    }
+    path.reverse();
+    Search { distance: Some(distance), path, visited }
+}
+
+fn not_found<N: Eq + Hash>(visited: HashSet<N>) -> Search<N> {
+    Search { distance: None, path: vec![], visited }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::*;
+
+    /// find returns the first cell of a maze holding `c`.
+    fn find(maze: &Grid<char>, c: char) -> GridCoord {
+        maze.coordinates().find(|&p| *maze.cell(p).unwrap() == c).unwrap()
+    }
+
+    /// walls is the cost of stepping around a maze, where `#` is a wall.
+    fn walls(_: &char, to: &char) -> Option<u64> {
+        (*to != '#').then_some(1)
+    }
+
+    /// check_path asserts that a path leads from S to E in steps of one cell.
+    fn check_path(maze: &Grid<char>, path: &[GridCoord]) {
+        assert_eq!(path.first(), Some(&find(maze, 'S')));
+        assert_eq!(path.last(), Some(&find(maze, 'E')));
+        for step in path.windows(2) {
+            let d = step[1] - step[0];
+            assert_eq!(d.x.abs() + d.y.abs(), 1, "{:?}", step);
+            assert_ne!(maze.cell(step[1]), Some(&'#'));
+        }
+    }
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#E#.
+.####.#.
+......#.
+";
+
+    #[test_case(MAZE, Some(11))]
+    #[test_case("S.E\n", Some(2); "straight")]
+    #[test_case("S#E\n", None; "walled off")]
+    fn test_mazes(maze: &str, distance: Option<u64>) {
+        let maze: Grid<char> = maze.parse().unwrap();
+        let (start, end) = (find(&maze, 'S'), find(&maze, 'E'));
+        let graph = GridGraph::new(&maze, walls);
+        let manhattan = |p: GridCoord| (p - end).x.unsigned_abs() as u64 + (p - end).y.unsigned_abs() as u64;
+        let searches = [
+            bfs(&graph, start, |p| p == end),
+            dijkstra(&graph, start, |p| p == end),
+            astar(&graph, start, |p| p == end, manhattan),
+        ];
+        for search in &searches {
+            assert_eq!(search.distance, distance);
+            match distance {
+                Some(distance) => {
+                    assert_eq!(search.path.len() as u64, distance + 1);
+                    assert_eq!(search.goal(), Some(&end));
+                    check_path(&maze, &search.path);
+                }
+                None => assert!(search.path.is_empty()),
+            }
+            assert!(search.visited.contains(&start));
+            assert!(search.path.iter().all(|p| search.visited.contains(p) || *p == end));
+        }
+        // Manhattan distance is a consistent heuristic when every step costs 1, so A* never
+        // explores a node twice, and only explores nodes that Dijkstra would reach first.
+        assert!(searches[2].visited.len() <= searches[1].visited.len());
+    }
+
+    #[test]
+    fn test_astar_with_an_inconsistent_heuristic() {
+        // The heuristic never overestimates, but drops by more than the cost of going from a to
+        // b, so b is first explored the costly way, straight from s, and has to be explored
+        // again once the cheaper way through a is found.
+        let graph: HashMap<char, Vec<(char, u64)>> = HashMap::from([
+            ('s', vec![('a', 1), ('b', 3)]),
+            ('a', vec![('b', 1)]),
+            ('b', vec![('g', 3)]),
+        ]);
+        let heuristic = |n| if n == 'a' { 4 } else { 0 };
+        let search = astar(&graph, 's', |n| n == 'g', heuristic);
+        assert_eq!(search.distance, Some(5));
+        assert_eq!(search.path, ['s', 'a', 'b', 'g']);
+        let cheapest = dijkstra(&graph, 's', |n| n == 'g');
+        assert_eq!((search.distance, search.path), (cheapest.distance, cheapest.path));
+    }
+
+    #[test]
+    fn test_unreachable_goal_visits_everything_reachable() {
+        let maze: Grid<char> = "S.#E\n..#.\n".parse().unwrap();
+        let search = bfs(&GridGraph::new(&maze, walls), find(&maze, 'S'), |p| p == find(&maze, 'E'));
+        assert_eq!(search.distance, None);
+        assert_eq!(search.goal(), None);
+        assert_eq!(search.visited.len(), 4);
+    }
+
+    #[test]
+    fn test_weighted_grid() {
+        // Each cell is the cost of stepping onto it. The cheapest way around is longer than the
+        // way straight through the middle.
+        let costs: Grid<u8> = "\
+11111
+19991
+11111
+"
+        .parse()
+        .unwrap();
+        let graph = GridGraph::new(&costs, |_, &to| Some(to as u64));
+        let (start, end) = (GridCoord::from_row_col(1, 0), GridCoord::from_row_col(1, 4));
+        let search = dijkstra(&graph, start, |p| p == end);
+        assert_eq!(search.distance, Some(6));
+        assert_eq!(search.path.len(), 7);
+        assert_eq!(bfs(&graph, start, |p| p == end).distance, Some(4));
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let maze: Grid<char> = "S..\n.#.\n..E\n".parse().unwrap();
+        let (start, end) = (find(&maze, 'S'), find(&maze, 'E'));
+        assert_eq!(bfs(&GridGraph::new(&maze, walls), start, |p| p == end).distance, Some(4));
+        let diagonal = bfs(&GridGraph::new(&maze, |_, _| Some(1)).with_diagonals(), start, |p| p == end);
+        assert_eq!(diagonal.path, [start, GridCoord::from_row_col(1, 1), end]);
+    }
+
+    #[test]
+    fn test_climbing() {
+        // Steps can depend on both cells, like only climbing one letter at a time.
+        let hill: Grid<char> = "abc\nzyd\nxwe\n".parse().unwrap();
+        let graph = GridGraph::new(&hill, |&from, &to| (to as u8 <= from as u8 + 1).then_some(1));
+        let search = bfs(&graph, GridCoord::from_row_col(0, 0), |p| hill.cell(p) == Some(&'e'));
+        assert_eq!(search.distance, Some(4));
+        let search = bfs(&graph, GridCoord::from_row_col(0, 0), |p| hill.cell(p) == Some(&'z'));
+        assert_eq!(search.distance, None);
+    }
+
+    #[test]
+    fn test_adjacency_list() {
+        let graph: HashMap<char, Vec<(char, u64)>> = HashMap::from([
+            ('a', vec![('b', 7), ('c', 1)]),
+            ('b', vec![('d', 1)]),
+            ('c', vec![('e', 1)]),
+            ('e', vec![('b', 1), ('d', 9)]),
+        ]);
+        let search = dijkstra(&graph, 'a', |n| n == 'd');
+        assert_eq!(search.distance, Some(4));
+        assert_eq!(search.path, ['a', 'c', 'e', 'b', 'd']);
+        let search = bfs(&graph, 'a', |n| n == 'd');
+        assert_eq!((search.distance, search.path), (Some(2), vec!['a', 'b', 'd']));
+        assert_eq!(astar(&graph, 'd', |n| n == 'a', |_| 0).distance, None);
+        let search = dijkstra(&graph, 'a', |n| n == 'a');
+        assert_eq!((search.distance, search.path), (Some(0), vec!['a']));
+    }
+}
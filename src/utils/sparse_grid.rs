@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use crate::utils::grid::{CellChar, Grid, GridCoord, NEIGHBORS4, NEIGHBORS8};
+
+/// SparseGrid is a grid without edges, which only stores the cells that have been set. Cells
+/// can be anywhere, including at negative rows and columns. It has the same methods as `Grid`
+/// for getting at cells, but its neighbors are the cells that have been set, hence
+/// `set_neighbors4` and `set_neighbors8` rather than `Grid`'s `neighbors4` and `neighbors8`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<GridCoord, T>,
+    bounds: Option<Bounds>,
+}
+
+/// Bounds is a rectangle of cells, from its top left corner to its bottom right one inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: GridCoord,
+    pub max: GridCoord,
+}
+
+impl Bounds {
+    pub fn width(&self) -> usize {
+        (self.max.col() - self.min.col() + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.row() - self.min.row() + 1) as usize
+    }
+
+    pub fn contains(&self, p: GridCoord) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// extend grows the bounds just enough to hold `p`.
+    fn extend(&mut self, p: GridCoord) {
+        self.min = GridCoord { x: self.min.x.min(p.x), y: self.min.y.min(p.y) };
+        self.max = GridCoord { x: self.max.x.max(p.x), y: self.max.y.max(p.y) };
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self { cells: HashMap::new(), bounds: None }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// len returns how many cells have been set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// bounds returns the smallest rectangle holding every cell that has been set, or `None`
+    /// if none has.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// in_bounds is true if `p` is within the bounds of the grid, whether or not it is set.
+    pub fn in_bounds(&self, p: GridCoord) -> bool {
+        self.bounds.is_some_and(|bounds| bounds.contains(p))
+    }
+
+    /// contains is true if the cell at `p` has been set.
+    pub fn contains(&self, p: GridCoord) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    /// insert sets the cell at `p`, growing the bounds to hold it, and returns what the cell
+    /// held before.
+    pub fn insert(&mut self, p: GridCoord, value: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(bounds) => bounds.extend(p),
+            None => self.bounds = Some(Bounds { min: p, max: p }),
+        }
+        self.cells.insert(p, value)
+    }
+
+    /// cell returns a read-only reference to the contents at the given location, if it has been
+    /// set.
+    pub fn cell(&self, p: GridCoord) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    /// cell_mut returns a mutable reference to the contents at the given location, if it has
+    /// been set.
+    pub fn cell_mut(&mut self, p: GridCoord) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    /// iter returns the cells that have been set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (GridCoord, &T)> {
+        self.cells.iter().map(|(&p, value)| (p, value))
+    }
+
+    /// set_neighbors4 returns the cells above, left of, right of and below `p`, in that order,
+    /// leaving out those that haven't been set. This is not what `Grid::neighbors4` does: it
+    /// keeps every cell inside the grid, and here unset cells are left out even inside the
+    /// bounds.
+    pub fn set_neighbors4(&self, p: GridCoord) -> impl Iterator<Item = GridCoord> + '_ {
+        NEIGHBORS4.iter().map(move |&step| p + step).filter(move |n| self.contains(*n))
+    }
+
+    /// set_neighbors8 returns the cells around `p`, diagonals included, row by row, leaving out
+    /// those that haven't been set. Like `set_neighbors4`, this differs from `Grid::neighbors8`,
+    /// which keeps every cell inside the grid.
+    pub fn set_neighbors8(&self, p: GridCoord) -> impl Iterator<Item = GridCoord> + '_ {
+        NEIGHBORS8.iter().map(move |&step| p + step).filter(move |n| self.contains(*n))
+    }
+
+    /// render_with draws the bounds of the grid as text, with a line for each row, using `cell`
+    /// to draw each cell, whether or not it has been set. An empty grid draws as nothing.
+    pub fn render_with(&self, mut cell: impl FnMut(GridCoord, Option<&T>) -> char) -> String {
+        let Some(bounds) = self.bounds else {
+            return String::new();
+        };
+        let mut drawing = String::with_capacity((bounds.width() + 1) * bounds.height());
+        for row in bounds.min.row()..=bounds.max.row() {
+            for col in bounds.min.col()..=bounds.max.col() {
+                let p = GridCoord::from_row_col(row, col);
+                drawing.push(cell(p, self.cell(p)));
+            }
+            drawing.push('\n');
+        }
+        drawing
+    }
+}
+
+impl<T> SparseGrid<T> where T: Default + Clone {
+    /// from_grid copies every cell of a grid, with its top left cell at `origin`.
+    pub fn from_grid(grid: &Grid<T>, origin: GridCoord) -> Self {
+        let mut sparse = Self::new();
+        for p in grid.coordinates() {
+            sparse.insert(origin + p, grid.cell(p).unwrap().clone());
+        }
+        sparse
+    }
+
+    /// to_grid copies the bounds of the grid into a dense grid, with the cells that haven't been
+    /// set left as the default. It also returns where the top left cell of the dense grid is in
+    /// this one, which gives this grid back when passed to `from_grid`.
+    pub fn to_grid(&self) -> (Grid<T>, GridCoord) {
+        let Some(bounds) = self.bounds else {
+            return (Grid::new(0, 0), GridCoord::default());
+        };
+        let mut grid = Grid::new(bounds.width(), bounds.height());
+        for (p, value) in self.iter() {
+            *grid.cell_mut(p - bounds.min).unwrap() = value.clone();
+        }
+        (grid, bounds.min)
+    }
+}
+
+impl<T> FromIterator<(GridCoord, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (GridCoord, T)>>(iter: I) -> Self {
+        let mut sparse = Self::new();
+        for (p, value) in iter {
+            sparse.insert(p, value);
+        }
+        sparse
+    }
+}
+
+impl<T> Display for SparseGrid<T> where T: CellChar {
+    /// Cells that haven't been set are drawn as `.`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render_with(|_, cell| cell.map_or('.', CellChar::to_char)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn at(row: isize, col: isize) -> GridCoord {
+        GridCoord::from_row_col(row, col)
+    }
+
+    /// A grid with three cells, around the origin:
+    ///
+    /// ```text
+    /// a..
+    /// ...
+    /// .bc
+    /// ```
+    fn sparse() -> SparseGrid<char> {
+        [(at(-1, -2), 'a'), (at(1, -1), 'b'), (at(1, 0), 'c')].into_iter().collect()
+    }
+
+    #[test]
+    fn test_cells() {
+        let mut grid = sparse();
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.cell(at(-1, -2)), Some(&'a'));
+        assert_eq!(grid.cell(at(0, 0)), None);
+        assert!(grid.contains(at(1, 0)));
+        *grid.cell_mut(at(1, 0)).unwrap() = 'C';
+        assert_eq!(grid.insert(at(1, 0), 'd'), Some('C'));
+        assert_eq!(grid.insert(at(1_000_000, 0), 'e'), None);
+        assert!(grid.cell_mut(at(5, 5)).is_none());
+        assert!(SparseGrid::<char>::new().is_empty());
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = sparse();
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(bounds, Bounds { min: at(-1, -2), max: at(1, 0) });
+        assert_eq!((bounds.width(), bounds.height()), (3, 3));
+        assert!(grid.in_bounds(at(0, -1)));
+        assert!(!grid.in_bounds(at(0, 1)));
+
+        grid.insert(at(-3, 4), 'd');
+        assert_eq!(grid.bounds(), Some(Bounds { min: at(-3, -2), max: at(1, 4) }));
+        assert_eq!(SparseGrid::<char>::new().bounds(), None);
+        assert!(!SparseGrid::<char>::new().in_bounds(at(0, 0)));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = sparse();
+        assert_eq!(grid.set_neighbors4(at(1, -1)).collect::<Vec<_>>(), [at(1, 0)]);
+        // (0, -1) and (1, -2) are inside the bounds, but haven't been set, so unlike with `Grid`
+        // they aren't neighbors of (0, -2).
+        assert!(grid.in_bounds(at(0, -1)) && grid.in_bounds(at(1, -2)));
+        assert_eq!(grid.set_neighbors4(at(0, -2)).collect::<Vec<_>>(), [at(-1, -2)]);
+        assert_eq!(grid.set_neighbors8(at(0, -1)).collect::<Vec<_>>(), [at(-1, -2), at(1, -1), at(1, 0)]);
+    }
+
+    #[test]
+    fn test_render() {
+        let grid = sparse();
+        assert_eq!(grid.render_with(|_, cell| *cell.unwrap_or(&' ')), "a  \n   \n bc\n");
+        assert_eq!(grid.to_string(), "a..\n...\n.bc\n");
+        assert_eq!(SparseGrid::<char>::new().to_string(), "");
+    }
+
+    #[test]
+    fn test_to_and_from_grid() {
+        let (grid, origin) = sparse().to_grid();
+        assert_eq!(origin, at(-1, -2));
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid.render_with(|_, &c| if c == char::default() { '.' } else { c }), "a..\n...\n.bc\n");
+
+        let dense: Grid<bool> = "#.\n.#\n#.\n".parse().unwrap();
+        let sparse = SparseGrid::from_grid(&dense, at(-5, 10));
+        assert_eq!(sparse.len(), 6);
+        assert_eq!(sparse.cell(at(-4, 11)), Some(&true));
+        assert_eq!(sparse.cell(at(-4, 10)), Some(&false));
+        assert_eq!(sparse.to_grid(), (dense, at(-5, 10)));
+
+        assert_eq!(SparseGrid::<bool>::new().to_grid(), (Grid::new(0, 0), at(0, 0)));
+    }
+}
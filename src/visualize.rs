@@ -0,0 +1,221 @@
+//! Visualizations of the days that are simulations, like the crane in day 5 or the rope in
+//! day 9.
+//!
+//! A solver that implements `Visualize` plays out a part as a series of text frames. Frames are
+//! handed to a sink as they are drawn, so a long simulation never has to hold them all. The
+//! sinks here either redraw the terminal in place (`Terminal`) or write the frames one after
+//! another to a file (`FrameLog`).
+
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result};
+
+use crate::solver::{Part, Solver};
+
+/// Frame is one picture of a simulation, with a line saying what is happening in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub title: String,
+    pub body: String,
+}
+
+impl Frame {
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Frame { title: title.into(), body: body.into() }
+    }
+}
+
+/// Visualize plays out a part of a day as frames. The answer still comes from `Solver`; the
+/// frames only show how it is reached.
+pub trait Visualize: Solver {
+    /// visualize runs the given part, handing each frame to `frame` as it is drawn. An error
+    /// from `frame` stops the simulation.
+    fn visualize(&self, part: Part, input: &Self::Input, frame: &mut dyn FnMut(Frame) -> Result<()>) -> Result<()>;
+}
+
+/// DynVisualize is the object safe version of `Visualize`, which parses the input itself.
+pub trait DynVisualize {
+    fn visualize(&self, part: Part, input: &str, frame: &mut dyn FnMut(Frame) -> Result<()>) -> Result<()>;
+}
+
+impl<S: Visualize> DynVisualize for S {
+    fn visualize(&self, part: Part, input: &str, frame: &mut dyn FnMut(Frame) -> Result<()>) -> Result<()> {
+        Visualize::visualize(self, part, &Solver::parse(self, input)?, frame)
+    }
+}
+
+/// Visualizer ties a day to the solver that visualizes it. Visualizers are created by
+/// `register_visualizers!`.
+pub struct Visualizer {
+    pub day: u8,
+    pub new: fn() -> Box<dyn DynVisualize>,
+}
+
+/// register_visualizers lists the solvers that implement `Visualize` in `VISUALIZERS`. The
+/// modules must already be declared by `register_solvers!`.
+#[macro_export]
+macro_rules! register_visualizers {
+    ($($module:ident :: $solver:ident),* $(,)?) => {
+        /// Every solver that can be visualized, in the order they were registered.
+        pub const VISUALIZERS: &[$crate::visualize::Visualizer] = &[
+            $(
+                $crate::visualize::Visualizer {
+                    day: $crate::solver::day_from_module(stringify!($module)),
+                    new: || -> Box<dyn $crate::visualize::DynVisualize> {
+                        Box::new(<$module::$solver as Default>::default())
+                    },
+                },
+            )*
+        ];
+    };
+}
+
+/// Terminal shows frames by redrawing the terminal in place with ANSI escape codes, at a fixed
+/// number of frames per second.
+pub struct Terminal<W: Write> {
+    out: W,
+    delay: Duration,
+    shown: usize,
+    last: Option<Instant>,
+}
+
+impl<W: Write> Terminal<W> {
+    pub fn new(out: W, fps: u32) -> Self {
+        let delay = Duration::from_secs(1) / fps.max(1);
+        Terminal { out, delay, shown: 0, last: None }
+    }
+
+    pub fn show(&mut self, frame: &Frame) -> Result<()> {
+        if let Some(last) = self.last {
+            thread::sleep(self.delay.saturating_sub(last.elapsed()));
+        } else {
+            // Start from a clear screen, so nothing is left over below the frames.
+            write!(self.out, "\x1b[2J")?;
+        }
+        self.shown += 1;
+        // Move to the top left, and clear the rest of every line and of the screen, rather than
+        // clearing the whole screen every frame, which flickers.
+        write!(self.out, "\x1b[H")?;
+        writeln!(self.out, "frame {}: {}\x1b[K", self.shown, frame.title)?;
+        for line in frame.body.lines() {
+            writeln!(self.out, "{}\x1b[K", line)?;
+        }
+        write!(self.out, "\x1b[J")?;
+        self.out.flush()?;
+        self.last = Some(Instant::now());
+        Ok(())
+    }
+}
+
+/// FrameLog writes frames one after another as plain text, each under a header line.
+pub struct FrameLog<W: Write> {
+    out: W,
+    written: usize,
+}
+
+impl<W: Write> FrameLog<W> {
+    pub fn new(out: W) -> Self {
+        FrameLog { out, written: 0 }
+    }
+
+    pub fn write(&mut self, frame: &Frame) -> Result<()> {
+        self.written += 1;
+        writeln!(self.out, "=== frame {}: {}", self.written, frame.title)?;
+        writeln!(self.out, "{}", frame.body.trim_end_matches('\n'))?;
+        Ok(())
+    }
+
+    /// finish flushes the output and returns how many frames were written.
+    pub fn finish(mut self) -> Result<usize> {
+        self.out.flush()?;
+        Ok(self.written)
+    }
+}
+
+/// viewport renders the window of `width` by `height` cells centred on `center`, using `cell`
+/// to draw each of them. Simulations that wander off far, like the rope in day 9, use it to keep
+/// frames the size of a terminal.
+pub fn viewport(center: (isize, isize), width: usize, height: usize, cell: impl Fn(isize, isize) -> char) -> String {
+    if width == 0 || height == 0 {
+        return String::new();
+    }
+    let (left, top) = (center.0 - width as isize / 2, center.1 - height as isize / 2);
+    let mut body = String::with_capacity((width + 1) * height);
+    for y in top..top + height as isize {
+        body.extend((left..left + width as isize).map(|x| cell(x, y)));
+        body.push('\n');
+    }
+    body
+}
+
+/// frames collects every frame of a part, for tests.
+pub fn frames(visualizer: &dyn DynVisualize, part: Part, input: &str) -> Result<Vec<Frame>> {
+    let mut frames = vec![];
+    visualizer.visualize(part, input, &mut |frame| {
+        frames.push(frame);
+        Ok(())
+    })?;
+    if frames.is_empty() {
+        return Err(eyre!("the visualization has no frames"));
+    }
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::inputs::{self, read_input};
+    use crate::{SOLVERS, VISUALIZERS};
+
+    #[test]
+    fn test_every_visualizer_is_a_registered_day() {
+        for visualizer in VISUALIZERS {
+            assert!(SOLVERS.iter().any(|r| r.day == visualizer.day), "day {}", visualizer.day);
+        }
+    }
+
+    #[test]
+    fn test_every_visualizer_draws_its_example() {
+        for visualizer in VISUALIZERS {
+            let input = read_input(inputs::example_input(visualizer.day)).unwrap();
+            for part in Part::ALL {
+                let frames = frames((visualizer.new)().as_ref(), part, &input).unwrap();
+                assert!(frames.iter().all(|f| !f.body.is_empty()), "day {} part {}", visualizer.day, part);
+            }
+        }
+    }
+
+    #[test]
+    fn test_terminal_redraws_in_place() {
+        let mut out = vec![];
+        let mut terminal = Terminal::new(&mut out, 1000);
+        terminal.show(&Frame::new("first", "ab\ncd\n")).unwrap();
+        terminal.show(&Frame::new("second", "ef\n")).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(
+            out,
+            "\x1b[2J\x1b[Hframe 1: first\x1b[K\nab\x1b[K\ncd\x1b[K\n\x1b[J\x1b[Hframe 2: second\x1b[K\nef\x1b[K\n\x1b[J"
+        );
+    }
+
+    #[test]
+    fn test_frame_log() {
+        let mut out = vec![];
+        let mut log = FrameLog::new(&mut out);
+        log.write(&Frame::new("first", "ab\n")).unwrap();
+        log.write(&Frame::new("second", "cd")).unwrap();
+        assert_eq!(log.finish().unwrap(), 2);
+        assert_eq!(String::from_utf8(out).unwrap(), "=== frame 1: first\nab\n=== frame 2: second\ncd\n");
+    }
+
+    #[test]
+    fn test_viewport() {
+        let body = viewport((0, 0), 3, 3, |x, y| if (x, y) == (0, 0) { 'H' } else { '.' });
+        assert_eq!(body, "...\n.H.\n...\n");
+        let body = viewport((5, -5), 4, 2, |x, y| if (x, y) == (3, -6) { '#' } else { '.' });
+        assert_eq!(body, "#...\n....\n");
+    }
+}
@@ -0,0 +1,190 @@
+//! Runs the binary the way our scripts do, checking its output and exit codes.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn aoc(args: &[&str], stdin: Option<&str>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rust-aoc2022"))
+        .args(args)
+        // Run from somewhere other than the crate root, to make sure nothing depends on the cwd.
+        .current_dir(std::env::temp_dir())
+        .env_remove("AOC_INPUTS")
+        .env_remove("AOC_LOG")
+        .env("RUST_BACKTRACE", "0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut input = child.stdin.take().unwrap();
+    input.write_all(stdin.unwrap_or("").as_bytes()).unwrap();
+    drop(input);
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_run_example() {
+    let output = aoc(&["run", "2", "--example"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Part 1: 15\nPart 2: 12\n");
+}
+
+#[test]
+fn test_logs_go_to_stderr() {
+    let output = aoc(&["run", "7", "--example"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Part 1: 95437\nPart 2: 24933642\n");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+
+    let output = aoc(&["run", "7", "--example", "--log", "day07=debug"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Part 1: 95437\nPart 2: 24933642\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[DEBUG day07] filesystem:"));
+}
+
+#[test]
+fn test_every_day_logs() {
+    for day in 1..=10 {
+        let module = format!("day{:02}", day);
+        let output = aoc(&["run", &day.to_string(), "--example", "--log", &format!("{}=debug", module)], None);
+        assert!(output.status.success(), "day {}", day);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(&format!("[DEBUG {}]", module)), "day {}: {:?}", day, stderr);
+    }
+}
+
+#[test]
+fn test_bad_log_filter_fails() {
+    let output = aoc(&["run", "7", "--example", "--log", "loud"], None);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid log level"));
+}
+
+#[test]
+fn test_run_one_part_from_stdin() {
+    let output = aoc(&["run", "1", "--part", "2", "--input", "-"], Some("1\n\n2\n\n3\n\n4\n"));
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Part 2: 9\n");
+}
+
+#[test]
+#[cfg(feature = "count-allocations")]
+fn test_run_counts_allocations() {
+    let output = aoc(&["run", "5", "--example", "--allocations"], None);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with("Part 1: CMZ\nPart 2: MCD\nAllocations:\n  parse  "), "{stdout}");
+    assert!(stdout.contains("  part2  "), "{stdout}");
+}
+
+#[test]
+#[cfg(not(feature = "count-allocations"))]
+fn test_allocations_need_the_feature() {
+    let output = aoc(&["run", "5", "--example", "--allocations"], None);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--features count-allocations"));
+}
+
+#[test]
+fn test_run_json() {
+    let output = aoc(&["run", "2", "--example", "--format", "json"], None);
+    assert!(output.status.success());
+    let records: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let records = records.as_array().unwrap();
+    assert_eq!(records.len(), 2);
+    for (record, (part, answer)) in records.iter().zip([(1, 15), (2, 12)]) {
+        assert_eq!(record["day"], 2);
+        assert_eq!(record["part"], part);
+        assert_eq!(record["answer"], answer);
+        assert!(record["input"].as_str().unwrap().ends_with("day02.txt"));
+        assert!(record["parse_time_ns"].is_u64());
+        assert!(record["solve_time_ns"].is_u64());
+        assert_eq!(record["error"], serde_json::Value::Null);
+    }
+}
+
+#[test]
+fn test_run_json_reports_errors() {
+    let output = aoc(&["run", "2", "--input", "-", "--format", "jsonl"], Some("A Y\nB Q\n"));
+    assert!(!output.status.success());
+    let stdout = stdout(&output);
+    let records: Vec<serde_json::Value> = stdout.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    for record in records {
+        assert_eq!(record["input"], "-");
+        assert_eq!(record["answer"], serde_json::Value::Null);
+        assert_eq!(record["error"]["kind"], "error");
+        assert!(record["error"]["message"].as_str().unwrap().contains("line 2"));
+    }
+}
+
+#[test]
+fn test_run_all_jsonl() {
+    let output = aoc(&["run-all", "--format", "jsonl"], None);
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    let records: Vec<serde_json::Value> = stdout.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(records.len(), 20);
+    // Records come in the order the parts finish in, so only which parts there are is checked.
+    let mut parts: Vec<(u64, u64)> =
+        records.iter().map(|r| (r["day"].as_u64().unwrap(), r["part"].as_u64().unwrap())).collect();
+    parts.sort();
+    assert_eq!(parts, (1..=10).flat_map(|day| [(day, 1), (day, 2)]).collect::<Vec<_>>());
+    assert!(records.iter().all(|r| r["error"].is_null() && !r["answer"].is_null()));
+}
+
+#[test]
+fn test_bad_input_fails() {
+    let output = aoc(&["run", "2", "--input", "-"], Some("A Y\nB Q\n"));
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 2"));
+}
+
+#[test]
+fn test_usage_errors_fail() {
+    assert!(!aoc(&["run", "26"], None).status.success());
+    assert!(!aoc(&["frobnicate"], None).status.success());
+}
+
+#[test]
+fn test_run_without_a_solver() {
+    for args in [&["run", "11"][..], &["run", "11", "--format", "json"]] {
+        let output = aoc(args, None);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("there is no solver for day 11"), "{}", stderr);
+        assert!(!stderr.contains("day11.txt"), "{}", stderr);
+    }
+}
+
+#[test]
+fn test_repl() {
+    let output = aoc(&["repl", "7", "--example"], Some("size /a\npart1\nsize /x\nreload\npart2\n"));
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    let replies: Vec<&str> = stdout.split("day07> ").skip(1).map(str::trim_end).collect();
+    assert_eq!(replies[..3], ["94853", "95437", "error: there is no \"x\" in /"]);
+    assert!(replies[3].starts_with("reloaded "), "{}", replies[3]);
+    assert_eq!(replies[4], "24933642");
+}
+
+#[test]
+fn test_list() {
+    let output = aoc(&["list"], None);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("day10::Day10Solver"));
+}
+
+#[test]
+fn test_generated_input_can_be_run() {
+    let generated = aoc(&["generate", "5", "--size", "50", "--seed", "7"], None);
+    assert!(generated.status.success());
+    assert_eq!(stdout(&generated), stdout(&aoc(&["generate", "5", "--size", "50", "--seed", "7"], None)));
+    let output = aoc(&["run", "5", "--input", "-"], Some(&stdout(&generated)));
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Part 1: "));
+}
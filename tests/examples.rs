@@ -0,0 +1,35 @@
+//! Runs every registered solver against every example from the puzzle descriptions, reporting
+//! all of the failures at once.
+
+use rust_aoc2022::answers::{check, Status};
+use rust_aoc2022::examples::discover;
+use rust_aoc2022::inputs::Inputs;
+use rust_aoc2022::SOLVERS;
+
+#[test]
+fn test_examples() {
+    let inputs = Inputs::new(None);
+    let examples = discover(&inputs).unwrap();
+
+    let mut failures = vec![];
+    for registration in SOLVERS {
+        if !examples.iter().any(|e| e.day == registration.day) {
+            failures.push(format!("day {} has no examples", registration.day));
+        }
+    }
+    for v in check(SOLVERS, &examples, &inputs) {
+        match v.status {
+            Status::Pass => {}
+            Status::Fail { expected, actual } => failures.push(format!(
+                "day {} part {} on {}: expected {}, got {}",
+                v.day,
+                v.part,
+                v.input.display(),
+                expected,
+                actual
+            )),
+            status => failures.push(format!("day {} part {} on {}: {:?}", v.day, v.part, v.input.display(), status)),
+        }
+    }
+    assert!(failures.is_empty(), "{} example checks failed:\n{}", failures.len(), failures.join("\n"));
+}
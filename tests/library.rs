@@ -0,0 +1,53 @@
+//! Uses the library the way another crate would, through its public API only.
+
+use rust_aoc2022::answer::Answer;
+use rust_aoc2022::answers::{load_answers, verify, Status};
+use rust_aoc2022::day02::Day2Solver;
+use rust_aoc2022::inputs::{self, read_input, Inputs};
+use rust_aoc2022::solver::{Part, Solver};
+use rust_aoc2022::utils::grid::{Grid, GridCoord};
+use rust_aoc2022::{get_solver, SOLVERS};
+
+#[test]
+fn test_solve_through_the_registry() {
+    let solver = get_solver(2).unwrap();
+    let parsed = solver.parse("A Y\nB X\nC Z\n").unwrap();
+    assert_eq!(solver.solve(Part::One, parsed.as_ref()).unwrap(), Answer::Integer(15));
+    assert_eq!(solver.solve(Part::Two, parsed.as_ref()).unwrap(), Answer::Integer(12));
+}
+
+#[test]
+fn test_solve_a_day_directly() {
+    let solver = Day2Solver::default();
+    let input = solver.parse(&read_input("unit_test/day02.txt").unwrap()).unwrap();
+    assert_eq!(solver.part1(&input).unwrap(), Answer::Integer(15));
+}
+
+#[test]
+fn test_every_day_solves_its_example() {
+    for registration in SOLVERS {
+        let input = read_input(inputs::example_input(registration.day)).unwrap();
+        for part in Part::ALL {
+            let answer = registration.solver().run(part, &input);
+            assert!(answer.is_ok(), "day {} part {}: {:?}", registration.day, part, answer);
+        }
+    }
+}
+
+#[test]
+fn test_verify_the_checked_in_answers() {
+    let inputs = Inputs::new(None);
+    let answers = load_answers(inputs.find("answers.json").unwrap()).unwrap();
+    for v in verify(SOLVERS, &answers, &inputs) {
+        assert_eq!(v.status, Status::Pass, "day {} part {}", v.day, v.part);
+    }
+}
+
+#[test]
+fn test_grid() {
+    let mut grid: Grid<u8> = Grid::new(3, 2);
+    *grid.cell_mut(GridCoord { x: 2, y: 1 }).unwrap() = 7;
+    assert_eq!(grid.cell(GridCoord { x: 2, y: 1 }), Some(&7));
+    assert_eq!(grid.cell(GridCoord { x: 3, y: 1 }), None);
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+}